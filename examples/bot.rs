@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use burz::ws::event::EventData;
-use burz::ws::message::{Hello, Message, OnlyData};
+use burz::ws::message::{Hello, HelloCode, Message, OnlyData};
 use burz::ws::Event;
 use burz::Bot;
 
@@ -14,7 +14,7 @@ use tokio_tungstenite::WebSocketStream;
 async fn _fake_gateway_no_pong_process(mut conn: WebSocketStream<TcpStream>) {
     let hello = Message::Hello(OnlyData {
         data: Hello {
-            code: 0,
+            code: HelloCode::Success,
             session_id: Some("x".to_string()),
         },
     });
@@ -24,7 +24,7 @@ async fn _fake_gateway_no_pong_process(mut conn: WebSocketStream<TcpStream>) {
 
     let event = Message::Event(EventData {
         sn: 1234,
-        event: Event::Null,
+        event: Event::Unknown(serde_json::Value::Null),
     });
 
     conn.feed(websocket::Message::Binary(event.encode()))
@@ -43,7 +43,7 @@ async fn _fake_gateway_no_pong_process(mut conn: WebSocketStream<TcpStream>) {
 async fn _fake_gateway_dup_message(mut conn: WebSocketStream<TcpStream>) {
     let hello = Message::Hello(OnlyData {
         data: Hello {
-            code: 0,
+            code: HelloCode::Success,
             session_id: Some("x".to_string()),
         },
     });
@@ -53,7 +53,7 @@ async fn _fake_gateway_dup_message(mut conn: WebSocketStream<TcpStream>) {
 
     let event = Message::Event(EventData {
         sn: 1,
-        event: Event::Null,
+        event: Event::Unknown(serde_json::Value::Null),
     });
 
     conn.feed(websocket::Message::Binary(event.encode()))
@@ -78,7 +78,7 @@ async fn _fake_gateway_dup_message(mut conn: WebSocketStream<TcpStream>) {
 async fn fake_gateway_misordered_message(mut conn: WebSocketStream<TcpStream>) {
     let hello = Message::Hello(OnlyData {
         data: Hello {
-            code: 0,
+            code: HelloCode::Success,
             session_id: Some("x".to_string()),
         },
     });
@@ -88,7 +88,7 @@ async fn fake_gateway_misordered_message(mut conn: WebSocketStream<TcpStream>) {
 
     let mut event = Message::Event(EventData {
         sn: 3,
-        event: Event::Null,
+        event: Event::Unknown(serde_json::Value::Null),
     });
 
     conn.feed(websocket::Message::Binary(event.encode()))
@@ -144,7 +144,13 @@ async fn main() {
 
     tokio::spawn(fake_gateway());
 
-    let bot = Bot::new(&token).unwrap();
+    let bot = Bot::new(&token)
+        .unwrap()
+        .with_gateway_url("ws://127.0.0.1:7777/gateway?token=x&compress=0")
+        .unwrap()
+        .with_handler(|_ctx, event| async move {
+            log::info!("Received event: {:?}", event);
+        });
 
     bot.run().await.unwrap();
 }
@@ -0,0 +1,55 @@
+use reqwest::{multipart, Body};
+use snafu::prelude::*;
+use tokio_util::io::ReaderStream;
+
+use super::Client;
+use crate::api::{
+    error::variant::*,
+    types::{Asset, AssetData, AssetSource},
+    Result,
+};
+
+static DEFAULT_ASSET_NAME: &str = "file";
+
+impl Client {
+    /// Call /asset/create, upload a file and get its hosted url.
+    ///
+    /// The url can be used in image/video/file message and card.
+    pub async fn upload_asset(&self, asset: Asset) -> Result<String> {
+        let mut name = asset.name;
+
+        let part = match asset.source {
+            AssetSource::Bytes(bytes) => multipart::Part::stream(bytes),
+            AssetSource::Reader(reader) => {
+                multipart::Part::stream(Body::wrap_stream(ReaderStream::new(reader)))
+            }
+            AssetSource::Path(path) => {
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .with_context(|_| ReadAssetFailed { path: &path })?;
+                let length = file
+                    .metadata()
+                    .await
+                    .with_context(|_| ReadAssetFailed { path: &path })?
+                    .len();
+
+                if name.is_none() {
+                    name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+                }
+
+                multipart::Part::stream_with_length(
+                    Body::wrap_stream(ReaderStream::new(file)),
+                    length,
+                )
+            }
+        };
+
+        let part = part.file_name(name.unwrap_or_else(|| DEFAULT_ASSET_NAME.to_string()));
+
+        let form = multipart::Form::new().part("file", part);
+
+        let data: AssetData = self.post_multipart("/asset/create", form).await?;
+
+        Ok(data.url)
+    }
+}
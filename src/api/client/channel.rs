@@ -0,0 +1,146 @@
+use futures_util::Stream;
+use serde::Serialize;
+use serde_json::json;
+
+use super::{paginate, Client};
+use crate::api::{types::*, Result};
+
+impl Client {
+    /// Call /channel/list, list channels of guild, optionally only given type
+    pub async fn list_channels(
+        &self,
+        guild_id: &str,
+        kind: Option<ChannelKind>,
+    ) -> Result<ChannelList> {
+        let kind = kind.map(|k| u8::from(k).to_string());
+        let mut query = vec![("guild_id", guild_id)];
+        if let Some(ref kind) = kind {
+            query.push(("type", kind));
+        }
+        self.get("/channel/list", query).await
+    }
+
+    /// Stream all channels of guild, optionally only given type, pages of /channel/list are
+    /// fetched on demand
+    pub fn channels<'a>(
+        &'a self,
+        guild_id: &'a str,
+        kind: Option<ChannelKind>,
+    ) -> impl Stream<Item = Result<Channel>> + 'a {
+        let kind = kind.map(|k| u8::from(k).to_string());
+        paginate(move |page| {
+            let mut query = vec![("guild_id", guild_id.to_string())];
+            if let Some(ref kind) = kind {
+                query.push(("type", kind.clone()));
+            }
+            async move { self.get_page("/channel/list", &query, page).await }
+        })
+    }
+
+    /// Call /channel/view, get channel detail
+    pub async fn view_channel(&self, channel_id: &str) -> Result<Channel> {
+        self.get("/channel/view", &[("target_id", channel_id)])
+            .await
+    }
+
+    /// Call /channel/create, create a channel
+    pub async fn create_channel(&self, channel: &CreateChannel) -> Result<Channel> {
+        self.post("/channel/create", channel).await
+    }
+
+    /// Call /channel/update, update channel info
+    pub async fn update_channel(&self, update: &UpdateChannel) -> Result<Channel> {
+        self.post("/channel/update", update).await
+    }
+
+    /// Call /channel/delete, delete a channel
+    pub async fn delete_channel(&self, channel_id: &str) -> Result<()> {
+        self.post_ignore_data("/channel/delete", &json!({ "channel_id": channel_id }))
+            .await
+    }
+
+    /// Call /channel/user-list, list users in voice channel
+    pub async fn list_channel_users(&self, channel_id: &str) -> Result<Vec<User>> {
+        self.get("/channel/user-list", &[("channel_id", channel_id)])
+            .await
+    }
+
+    /// Call /channel/move-user, move users to another voice channel
+    pub async fn move_channel_users(&self, channel_id: &str, user_ids: &[&str]) -> Result<()> {
+        self.post_ignore_data(
+            "/channel/move-user",
+            &json!({ "target_id": channel_id, "user_ids": user_ids }),
+        )
+        .await
+    }
+
+    /// Call /channel-role/index, get permission overwrites of channel
+    pub async fn channel_permissions(&self, channel_id: &str) -> Result<ChannelPermissions> {
+        self.get("/channel-role/index", &[("channel_id", channel_id)])
+            .await
+    }
+
+    /// Call /channel-role/create, create an empty permission overwrite for role or user
+    pub async fn create_channel_overwrite(
+        &self,
+        channel_id: &str,
+        target: &OverwriteTarget,
+    ) -> Result<ChannelOverwrite> {
+        self.post(
+            "/channel-role/create",
+            &OverwriteBody::new(channel_id, target),
+        )
+        .await
+    }
+
+    /// Call /channel-role/update, update permission overwrite for role or user
+    pub async fn update_channel_overwrite(
+        &self,
+        channel_id: &str,
+        target: &OverwriteTarget,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> Result<ChannelOverwrite> {
+        let body = OverwriteBody {
+            allow: Some(allow),
+            deny: Some(deny),
+            ..OverwriteBody::new(channel_id, target)
+        };
+        self.post("/channel-role/update", &body).await
+    }
+
+    /// Call /channel-role/delete, delete permission overwrite for role or user
+    pub async fn delete_channel_overwrite(
+        &self,
+        channel_id: &str,
+        target: &OverwriteTarget,
+    ) -> Result<()> {
+        self.post_ignore_data(
+            "/channel-role/delete",
+            &OverwriteBody::new(channel_id, target),
+        )
+        .await
+    }
+}
+
+#[derive(Serialize)]
+struct OverwriteBody<'a> {
+    channel_id: &'a str,
+    #[serde(flatten)]
+    target: &'a OverwriteTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow: Option<Permissions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deny: Option<Permissions>,
+}
+
+impl<'a> OverwriteBody<'a> {
+    fn new(channel_id: &'a str, target: &'a OverwriteTarget) -> Self {
+        Self {
+            channel_id,
+            target,
+            allow: None,
+            deny: None,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use futures_util::Stream;
+use serde_json::json;
+
+use super::{paginate, walk_messages, Client, NO_QUERY};
+use crate::api::{types::*, Result};
+
+impl Client {
+    /// Call /user-chat/list, list private chats of bot
+    pub async fn list_user_chats(&self) -> Result<UserChatList> {
+        self.get("/user-chat/list", NO_QUERY).await
+    }
+
+    /// Stream all private chats of bot, pages of /user-chat/list are fetched on demand
+    pub fn user_chats(&self) -> impl Stream<Item = Result<UserChat>> + '_ {
+        paginate(move |page| self.get_page("/user-chat/list", NO_QUERY, page))
+    }
+
+    /// Call /user-chat/view, get private chat detail
+    pub async fn view_user_chat(&self, chat_code: &str) -> Result<UserChat> {
+        self.get("/user-chat/view", &[("chat_code", chat_code)])
+            .await
+    }
+
+    /// Call /user-chat/create, create a private chat with user
+    pub async fn create_user_chat(&self, target_id: &str) -> Result<UserChat> {
+        self.post("/user-chat/create", &json!({ "target_id": target_id }))
+            .await
+    }
+
+    /// Call /user-chat/delete, delete a private chat
+    pub async fn delete_user_chat(&self, chat_code: &str) -> Result<()> {
+        self.post_ignore_data("/user-chat/delete", &json!({ "chat_code": chat_code }))
+            .await
+    }
+
+    /// Call /direct-message/list, list messages in private chat
+    pub async fn list_direct_messages(
+        &self,
+        query: &ListDirectMessages,
+    ) -> Result<DirectMessageList> {
+        self.get_query("/direct-message/list", query).await
+    }
+
+    /// Stream messages in private chat, walking from the reference message of query
+    /// in its direction(before if not set) using /direct-message/list
+    pub fn direct_messages(
+        &self,
+        query: ListDirectMessages,
+    ) -> impl Stream<Item = Result<DirectMessage>> + '_ {
+        walk_messages(
+            query.msg_id.clone(),
+            query.flag,
+            |m: &DirectMessage| &m.id,
+            move |msg_id, flag| {
+                let query = ListDirectMessages {
+                    msg_id,
+                    flag,
+                    ..query.clone()
+                };
+                async move { Ok(self.list_direct_messages(&query).await?.items) }
+            },
+        )
+    }
+
+    /// Call /direct-message/view, get private message detail
+    pub async fn view_direct_message(
+        &self,
+        chat_code: &str,
+        msg_id: &str,
+    ) -> Result<DirectMessage> {
+        self.get(
+            "/direct-message/view",
+            &[("chat_code", chat_code), ("msg_id", msg_id)],
+        )
+        .await
+    }
+
+    /// Call /direct-message/create, send a private message
+    pub async fn create_direct_message(
+        &self,
+        message: &CreateDirectMessage,
+    ) -> Result<CreatedMessage> {
+        self.post("/direct-message/create", message).await
+    }
+
+    /// Call /direct-message/update, update content of a private message
+    pub async fn update_direct_message(&self, update: &UpdateDirectMessage) -> Result<()> {
+        self.post_ignore_data("/direct-message/update", update)
+            .await
+    }
+
+    /// Call /direct-message/delete, delete a private message
+    pub async fn delete_direct_message(&self, msg_id: &str) -> Result<()> {
+        self.post_ignore_data("/direct-message/delete", &json!({ "msg_id": msg_id }))
+            .await
+    }
+
+    /// Call /direct-message/reaction-list, list users who reacted with the emoji
+    pub async fn list_direct_message_reactions(
+        &self,
+        msg_id: &str,
+        emoji: &str,
+    ) -> Result<Vec<ReactionUser>> {
+        self.get(
+            "/direct-message/reaction-list",
+            &[("msg_id", msg_id), ("emoji", emoji)],
+        )
+        .await
+    }
+
+    /// Call /direct-message/add-reaction, add a reaction to private message
+    pub async fn add_direct_message_reaction(&self, msg_id: &str, emoji: &str) -> Result<()> {
+        self.post_ignore_data(
+            "/direct-message/add-reaction",
+            &json!({ "msg_id": msg_id, "emoji": emoji }),
+        )
+        .await
+    }
+
+    /// Call /direct-message/delete-reaction, remove a reaction from private message.
+    ///
+    /// `user_id` is the user whose reaction to remove, bot itself if not set.
+    pub async fn delete_direct_message_reaction(
+        &self,
+        msg_id: &str,
+        emoji: &str,
+        user_id: Option<&str>,
+    ) -> Result<()> {
+        let mut body = json!({ "msg_id": msg_id, "emoji": emoji });
+        if let Some(user_id) = user_id {
+            body["user_id"] = user_id.into();
+        }
+        self.post_ignore_data("/direct-message/delete-reaction", &body)
+            .await
+    }
+}
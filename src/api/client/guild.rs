@@ -0,0 +1,115 @@
+use futures_util::Stream;
+use serde_json::json;
+
+use super::{paginate, Client, NO_QUERY};
+use crate::api::{types::*, Result};
+
+impl Client {
+    /// Call /guild/list, list guilds bot joined
+    pub async fn list_guilds(&self) -> Result<GuildList> {
+        self.get("/guild/list", NO_QUERY).await
+    }
+
+    /// Stream all guilds bot joined, pages of /guild/list are fetched on demand
+    pub fn guilds(&self) -> impl Stream<Item = Result<Guild>> + '_ {
+        paginate(move |page| self.get_page("/guild/list", NO_QUERY, page))
+    }
+
+    /// Call /guild/view, get guild detail
+    pub async fn view_guild(&self, guild_id: &str) -> Result<GuildDetail> {
+        self.get("/guild/view", &[("guild_id", guild_id)]).await
+    }
+
+    /// Call /guild/user-list, list members of guild
+    pub async fn list_guild_members(&self, query: &ListGuildMembers) -> Result<GuildMemberList> {
+        self.get_query("/guild/user-list", query).await
+    }
+
+    /// Stream all members of guild matching the query, pages of /guild/user-list are fetched on demand
+    pub fn guild_members(
+        &self,
+        query: ListGuildMembers,
+    ) -> impl Stream<Item = Result<GuildMember>> + '_ {
+        paginate(move |page| {
+            let query = query.clone();
+            async move {
+                let list: GuildMemberList = self.get_page("/guild/user-list", &query, page).await?;
+                Ok(Page {
+                    items: list.items,
+                    meta: list.meta,
+                })
+            }
+        })
+    }
+
+    /// Call /guild/nickname, set nickname of a member.
+    ///
+    /// `user_id` is bot itself if not set, `nickname` is reset if not set.
+    pub async fn set_guild_nickname(
+        &self,
+        guild_id: &str,
+        user_id: Option<&str>,
+        nickname: Option<&str>,
+    ) -> Result<()> {
+        let mut body = json!({ "guild_id": guild_id });
+        if let Some(user_id) = user_id {
+            body["user_id"] = user_id.into();
+        }
+        if let Some(nickname) = nickname {
+            body["nickname"] = nickname.into();
+        }
+        self.post_ignore_data("/guild/nickname", &body).await
+    }
+
+    /// Call /guild/leave, make bot leave the guild
+    pub async fn leave_guild(&self, guild_id: &str) -> Result<()> {
+        self.post_ignore_data("/guild/leave", &json!({ "guild_id": guild_id }))
+            .await
+    }
+
+    /// Call /guild/kickout, kick a member out of guild
+    pub async fn kick_guild_member(&self, guild_id: &str, user_id: &str) -> Result<()> {
+        self.post_ignore_data(
+            "/guild/kickout",
+            &json!({ "guild_id": guild_id, "target_id": user_id }),
+        )
+        .await
+    }
+
+    /// Call /guild-mute/list, list muted members of guild
+    pub async fn list_guild_mutes(&self, guild_id: &str) -> Result<GuildMuteList> {
+        self.get(
+            "/guild-mute/list",
+            &[("guild_id", guild_id), ("return_type", "detail")],
+        )
+        .await
+    }
+
+    /// Call /guild-mute/create, mute a member
+    pub async fn create_guild_mute(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        mute_type: MuteType,
+    ) -> Result<()> {
+        self.post_ignore_data(
+            "/guild-mute/create",
+            &json!({ "guild_id": guild_id, "user_id": user_id, "type": mute_type }),
+        )
+        .await
+    }
+
+    /// Call /guild-mute/delete, unmute a member
+    pub async fn delete_guild_mute(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        mute_type: MuteType,
+    ) -> Result<()> {
+        self.post_ignore_data(
+            "/guild-mute/delete",
+            &json!({ "guild_id": guild_id, "user_id": user_id, "type": mute_type }),
+        )
+        .await
+    }
+}
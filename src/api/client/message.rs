@@ -0,0 +1,51 @@
+use futures_util::Stream;
+use serde_json::json;
+
+use super::{walk_messages, Client};
+use crate::api::{types::*, Result};
+
+impl Client {
+    /// Call /message/create, send a message to channel
+    pub async fn create_message(&self, message: &CreateMessage) -> Result<CreatedMessage> {
+        self.post("/message/create", message).await
+    }
+
+    /// Call /message/update, update content of a message
+    pub async fn update_message(&self, update: &UpdateMessage) -> Result<()> {
+        self.post_ignore_data("/message/update", update).await
+    }
+
+    /// Call /message/delete, delete a message
+    pub async fn delete_message(&self, msg_id: &str) -> Result<()> {
+        self.post_ignore_data("/message/delete", &json!({ "msg_id": msg_id }))
+            .await
+    }
+
+    /// Call /message/list, list messages in channel
+    pub async fn list_messages(&self, query: &ListMessages) -> Result<MessageList> {
+        self.get_query("/message/list", query).await
+    }
+
+    /// Stream messages in channel, walking from the reference message of query
+    /// in its direction(before if not set) using /message/list
+    pub fn messages(&self, query: ListMessages) -> impl Stream<Item = Result<ChannelMessage>> + '_ {
+        walk_messages(
+            query.msg_id.clone(),
+            query.flag,
+            |m: &ChannelMessage| &m.id,
+            move |msg_id, flag| {
+                let query = ListMessages {
+                    msg_id,
+                    flag,
+                    ..query.clone()
+                };
+                async move { Ok(self.list_messages(&query).await?.items) }
+            },
+        )
+    }
+
+    /// Call /message/view, get message detail
+    pub async fn view_message(&self, msg_id: &str) -> Result<ChannelMessage> {
+        self.get("/message/view", &[("msg_id", msg_id)]).await
+    }
+}
@@ -0,0 +1,367 @@
+mod asset;
+mod channel;
+mod direct_message;
+mod guild;
+mod message;
+mod role;
+
+use std::{borrow::Borrow, collections::HashMap, future::Future};
+
+use futures_util::{stream, Stream, TryStreamExt};
+use reqwest::{multipart, Method, RequestBuilder, StatusCode};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Serialize,
+};
+use snafu::prelude::*;
+
+use super::error::{variant::*, ErrorCode};
+use super::rate_limit::{BucketState, RateLimiter};
+use super::types::*;
+use super::Result;
+
+static BASE_URL: &str = "https://www.kaiheila.cn/api/v3";
+
+static NO_QUERY: &[(&str, &str)] = &[];
+
+/// max retry times when server returns 429 Too Many Requests
+const RATE_LIMITED_RETRY_MAX: usize = 3;
+
+static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+
+/// Kaiheila HTTP API Client
+#[derive(Debug)]
+pub struct Client {
+    client: reqwest::Client,
+    base_url: String,
+    rate_limiter: RateLimiter,
+}
+
+impl Client {
+    fn new<S: AsRef<str> + ?Sized>(auth_type: &'static str, token: &S) -> Result<Self> {
+        let token = token.as_ref();
+        let auth_header_value = format!("{} {}", auth_type, token).parse().map_err(|_| {
+            TokenInvalid {
+                token: token.to_string(),
+            }
+            .build()
+        })?;
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::AUTHORIZATION, auth_header_value);
+
+        let client = reqwest::Client::builder()
+            .gzip(true)
+            .deflate(true)
+            .user_agent(APP_USER_AGENT)
+            .default_headers(headers)
+            .build()
+            .context(ClientCreateFailed)?;
+
+        Ok(Self {
+            client,
+            base_url: BASE_URL.to_string(),
+            rate_limiter: RateLimiter::default(),
+        })
+    }
+
+    /// create a new api client using bot token
+    pub fn new_from_bot_token<S: AsRef<str> + ?Sized>(token: &S) -> Result<Self> {
+        Self::new("Bot", token)
+    }
+
+    /// create a new api client using oauth2 token
+    pub fn new_from_oauth2_token<S: AsRef<str> + ?Sized>(token: &S) -> Result<Self> {
+        Self::new("Bearer", token)
+    }
+
+    /// Send requests to given base url instead of the official one.
+    ///
+    /// This is mainly used for testing with a local fake api server.
+    pub fn with_base_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    async fn request<R, P, F>(&self, method: Method, path: &P, build: F) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        F: FnOnce(RequestBuilder) -> RequestBuilder,
+        R: DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, path.as_ref());
+
+        let mut req = build(self.client.request(method.clone(), &url))
+            .build()
+            .context(BuildRequestFailed)?;
+
+        let mut retry_count = 0;
+
+        let resp = loop {
+            // streaming body can't be cloned, so it will not be retried
+            let retry_req = req.try_clone();
+
+            self.rate_limiter.acquire(path.as_ref()).await;
+
+            let resp = self
+                .client
+                .execute(req)
+                .await
+                .with_context(|_| RequestFailed {
+                    method: method.clone(),
+                    url: &url,
+                })?;
+
+            let limited = resp.status() == StatusCode::TOO_MANY_REQUESTS;
+
+            self.rate_limiter
+                .update(path.as_ref(), resp.headers(), limited);
+
+            match retry_req {
+                Some(r) if limited && retry_count < RATE_LIMITED_RETRY_MAX => {
+                    retry_count += 1;
+                    log::warn!(
+                        "{} {} is rate limited, retry {}/{}",
+                        method,
+                        url,
+                        retry_count,
+                        RATE_LIMITED_RETRY_MAX
+                    );
+                    req = r;
+                }
+                _ => break resp,
+            }
+        };
+
+        ensure!(
+            resp.status() == StatusCode::OK,
+            HTTPStatusNotOK {
+                method,
+                url: &url,
+                status_code: resp.status()
+            }
+        );
+
+        let body = resp
+            .bytes()
+            .await
+            .with_context(|_| RequestFailed { method, url: &url })?;
+
+        let result: Response<R> =
+            serde_json::from_slice(&body).with_context(|_| ParseBodyFailed { body })?;
+
+        ensure!(
+            result.code == 0,
+            CodeNotZero {
+                code: ErrorCode::from(result.code),
+                message: result.message
+            }
+        );
+
+        Ok(result.data)
+    }
+
+    /// Current rate limit state of all known buckets, for diagnostics
+    pub fn rate_limit_buckets(&self) -> HashMap<String, BucketState> {
+        self.rate_limiter.buckets()
+    }
+
+    /// Send a GET request to api `path` with query pairs, decode `data` field of response as `R`
+    pub async fn get<R, P, Q, K, V>(&self, path: &P, query: Q) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        Q: IntoIterator,
+        Q::Item: Borrow<(K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+        R: DeserializeOwned,
+    {
+        self.request(Method::GET, path, |mut req| {
+            for q in query.into_iter() {
+                let (k, v) = q.borrow();
+                req = req.query(&[(k.as_ref(), v.as_ref())]);
+            }
+            req
+        })
+        .await
+    }
+
+    async fn get_query<R, P, Q>(&self, path: &P, query: &Q) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        Q: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.request(Method::GET, path, |req| req.query(query))
+            .await
+    }
+
+    async fn get_page<R, P, Q>(&self, path: &P, query: &Q, page: u32) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        Q: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.request(Method::GET, path, |req| {
+            req.query(query).query(&[("page", page)])
+        })
+        .await
+    }
+
+    /// Send a POST request to api `path` with json body, decode `data` field of response as `R`
+    pub async fn post<R, P, B>(&self, path: &P, body: &B) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.request(Method::POST, path, |req| req.json(body)).await
+    }
+
+    async fn post_ignore_data<P, B>(&self, path: &P, body: &B) -> Result<()>
+    where
+        P: AsRef<str> + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.post::<IgnoredAny, _, _>(path, body).await.map(drop)
+    }
+
+    /// Send a POST request to api `path` with multipart body, decode `data` field of response as `R`
+    pub async fn post_multipart<R, P>(&self, path: &P, form: multipart::Form) -> Result<R>
+    where
+        P: AsRef<str> + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.request(Method::POST, path, |req| req.multipart(form))
+            .await
+    }
+
+    /// Call /gateway/index, get gateway url
+    pub async fn gateway_url(&self) -> Result<String> {
+        let data: GatewayIndexData = self.get("/gateway/index", &[("compress", "1")]).await?;
+        Ok(data.url)
+    }
+
+    /// Call /user/me, get current user info
+    pub async fn me(&self) -> Result<User> {
+        self.get("/user/me", NO_QUERY).await
+    }
+}
+
+/// Yield items of a paginated list api, the next page is fetched only when the
+/// current one is consumed. `fetch` gets page number starting from 1.
+fn paginate<'a, T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(u32) -> Fut + 'a,
+    Fut: Future<Output = Result<Page<T>>> + 'a,
+{
+    stream::try_unfold((fetch, Some(1)), |(mut fetch, page)| async move {
+        let page = match page {
+            Some(page) => page,
+            None => return Ok(None),
+        };
+
+        let result = fetch(page).await?;
+        let next = (result.meta.has_next() && !result.items.is_empty()).then_some(page + 1);
+
+        Ok(Some((
+            stream::iter(result.items.into_iter().map(Ok)),
+            (fetch, next),
+        )))
+    })
+    .try_flatten()
+}
+
+/// Yield messages of a message list api, walking from reference message `msg_id`
+/// in `flag` direction(before if not set) batch by batch.
+///
+/// Kaiheila returns messages of a batch in ascending time order, so the first one is
+/// used as next reference message when walking backward, and the last one when forward.
+/// [ListFlag::Around] only fetches one batch.
+fn walk_messages<'a, T, F, Fut>(
+    msg_id: Option<String>,
+    flag: Option<ListFlag>,
+    id: fn(&T) -> &str,
+    fetch: F,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>, Option<ListFlag>) -> Fut + 'a,
+    Fut: Future<Output = Result<Vec<T>>> + 'a,
+{
+    let direction = flag.unwrap_or(ListFlag::Before);
+
+    stream::try_unfold(
+        (fetch, Some((msg_id, flag))),
+        move |(mut fetch, cursor)| async move {
+            let (msg_id, flag) = match cursor {
+                Some(cursor) => cursor,
+                None => return Ok(None),
+            };
+
+            let items = fetch(msg_id.clone(), flag).await?;
+
+            let next = match direction {
+                ListFlag::Around => None,
+                ListFlag::Before => items.first(),
+                ListFlag::After => items.last(),
+            }
+            .map(|item| id(item).to_string())
+            .filter(|next| msg_id.as_ref() != Some(next))
+            .map(|next| (Some(next), Some(direction)));
+
+            Ok(Some((
+                stream::iter(items.into_iter().map(Ok)),
+                (fetch, next),
+            )))
+        },
+    )
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let items: Vec<u32> = paginate(|page| async move {
+            Ok(Page {
+                items: vec![page * 10, page * 10 + 1],
+                meta: PageMeta {
+                    page,
+                    page_total: 3,
+                    page_size: 2,
+                    total: 6,
+                },
+            })
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![10, 11, 20, 21, 30, 31]);
+    }
+
+    #[tokio::test]
+    async fn test_walk_messages() {
+        let ids: Vec<String> = walk_messages(
+            Some("5".to_string()),
+            None,
+            |id: &String| id,
+            |msg_id, flag| async move {
+                let before: u32 = msg_id.unwrap().parse().unwrap();
+                assert!(flag.is_none() || flag == Some(ListFlag::Before));
+                Ok((before.saturating_sub(2)..before)
+                    .map(|id| id.to_string())
+                    .collect())
+            },
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(ids, vec!["3", "4", "1", "2", "0"]);
+    }
+}
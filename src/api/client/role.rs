@@ -0,0 +1,72 @@
+use futures_util::Stream;
+use serde_json::json;
+
+use super::{paginate, Client};
+use crate::api::{types::*, Result};
+
+impl Client {
+    /// Call /guild-role/list, list roles of guild
+    pub async fn list_roles(&self, guild_id: &str) -> Result<RoleList> {
+        self.get("/guild-role/list", &[("guild_id", guild_id)])
+            .await
+    }
+
+    /// Stream all roles of guild, pages of /guild-role/list are fetched on demand
+    pub fn roles<'a>(&'a self, guild_id: &'a str) -> impl Stream<Item = Result<Role>> + 'a {
+        paginate(move |page| async move {
+            self.get_page("/guild-role/list", &[("guild_id", guild_id)], page)
+                .await
+        })
+    }
+
+    /// Call /guild-role/create, create a role, server gives a default name if not set
+    pub async fn create_role(&self, guild_id: &str, name: Option<&str>) -> Result<Role> {
+        let mut body = json!({ "guild_id": guild_id });
+        if let Some(name) = name {
+            body["name"] = name.into();
+        }
+        self.post("/guild-role/create", &body).await
+    }
+
+    /// Call /guild-role/update, update role info
+    pub async fn update_role(&self, update: &UpdateRole) -> Result<Role> {
+        self.post("/guild-role/update", update).await
+    }
+
+    /// Call /guild-role/delete, delete a role
+    pub async fn delete_role(&self, guild_id: &str, role_id: u64) -> Result<()> {
+        self.post_ignore_data(
+            "/guild-role/delete",
+            &json!({ "guild_id": guild_id, "role_id": role_id }),
+        )
+        .await
+    }
+
+    /// Call /guild-role/grant, grant a role to user
+    pub async fn grant_role(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        role_id: u64,
+    ) -> Result<RoleGrant> {
+        self.post(
+            "/guild-role/grant",
+            &json!({ "guild_id": guild_id, "user_id": user_id, "role_id": role_id }),
+        )
+        .await
+    }
+
+    /// Call /guild-role/revoke, revoke a role from user
+    pub async fn revoke_role(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        role_id: u64,
+    ) -> Result<RoleGrant> {
+        self.post(
+            "/guild-role/revoke",
+            &json!({ "guild_id": guild_id, "user_id": user_id, "role_id": role_id }),
+        )
+        .await
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use snafu::prelude::*;
 
 /// API Error
@@ -56,12 +58,174 @@ pub enum Error {
         source: serde_json::Error,
     },
 
+    /// read local file for asset upload failed
+    #[snafu(display("read asset file {} failed: {source}", path.display()))]
+    ReadAssetFailed {
+        /// file path
+        path: std::path::PathBuf,
+        /// source io error
+        source: std::io::Error,
+    },
+
     /// api response code is not zero
     #[snafu(display("api return error code {code}, {message}"))]
     CodeNotZero {
         /// received response code
-        code: i64,
+        code: ErrorCode,
         /// received message
         message: String,
     },
 }
+
+impl Error {
+    /// error code returned by server, if the request reached api logic
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::CodeNotZero { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// if the same request may succeed when retried later
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RequestFailed { source, .. } => source.is_timeout() || source.is_connect(),
+            Self::HTTPStatusNotOK { status_code, .. } => {
+                *status_code == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || status_code.is_server_error()
+            }
+            Self::CodeNotZero { code, .. } => code.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// if the request is rejected because bot has no permission to do it
+    pub fn is_permission_error(&self) -> bool {
+        match self {
+            Self::HTTPStatusNotOK { status_code, .. } => {
+                *status_code == reqwest::StatusCode::FORBIDDEN
+            }
+            Self::CodeNotZero { code, .. } => code.is_permission_error(),
+            _ => false,
+        }
+    }
+
+    /// if the request is rejected because bot token is missing, invalid or expired
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            Self::HTTPStatusNotOK { status_code, .. } => {
+                *status_code == reqwest::StatusCode::UNAUTHORIZED
+            }
+            Self::CodeNotZero { code, .. } => code.is_auth_error(),
+            _ => false,
+        }
+    }
+}
+
+/// Error code in api response
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// request parameters are invalid, 40000-40099
+    Validation(i64),
+    /// token is missing, 40100
+    TokenMissing,
+    /// token is invalid, 40101
+    TokenInvalid,
+    /// token verify failed, 40102
+    TokenVerifyFailed,
+    /// token is expired, 40103
+    TokenExpired,
+    /// no permission, 40300
+    PermissionDenied,
+    /// target resource not found, 40400
+    NotFound,
+    /// too many requests, 42900
+    RateLimited,
+    /// server internal error, 50000
+    ServerError,
+    /// other codes
+    Other(i64),
+}
+
+impl ErrorCode {
+    /// if the same request may succeed when retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RateLimited | Self::ServerError)
+    }
+
+    /// if the request is rejected because bot has no permission to do it
+    pub fn is_permission_error(&self) -> bool {
+        matches!(self, Self::PermissionDenied)
+    }
+
+    /// if the request is rejected because of token problems
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::TokenMissing | Self::TokenInvalid | Self::TokenVerifyFailed | Self::TokenExpired
+        )
+    }
+}
+
+impl From<i64> for ErrorCode {
+    fn from(code: i64) -> Self {
+        match code {
+            40000..=40099 => Self::Validation(code),
+            40100 => Self::TokenMissing,
+            40101 => Self::TokenInvalid,
+            40102 => Self::TokenVerifyFailed,
+            40103 => Self::TokenExpired,
+            40300 => Self::PermissionDenied,
+            40400 => Self::NotFound,
+            42900 => Self::RateLimited,
+            50000 => Self::ServerError,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl From<ErrorCode> for i64 {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Validation(code) | ErrorCode::Other(code) => code,
+            ErrorCode::TokenMissing => 40100,
+            ErrorCode::TokenInvalid => 40101,
+            ErrorCode::TokenVerifyFailed => 40102,
+            ErrorCode::TokenExpired => 40103,
+            ErrorCode::PermissionDenied => 40300,
+            ErrorCode::NotFound => 40400,
+            ErrorCode::RateLimited => 42900,
+            ErrorCode::ServerError => 50000,
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", i64::from(*self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_code_round_trip() {
+        for code in [
+            40000, 40012, 40100, 40101, 40102, 40103, 40300, 40400, 42900, 50000, 12345,
+        ] {
+            assert_eq!(i64::from(ErrorCode::from(code)), code);
+        }
+
+        assert_eq!(ErrorCode::from(40003), ErrorCode::Validation(40003));
+        assert!(ErrorCode::from(40300).is_permission_error());
+        assert!(!ErrorCode::from(40300).is_auth_error());
+        for code in [40100, 40101, 40102, 40103] {
+            assert!(ErrorCode::from(code).is_auth_error());
+            assert!(!ErrorCode::from(code).is_permission_error());
+        }
+        assert!(ErrorCode::from(42900).is_retryable());
+        assert!(!ErrorCode::from(40400).is_retryable());
+    }
+}
@@ -0,0 +1,69 @@
+//! local fake api server for tests
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, StatusCode,
+};
+use tokio::sync::mpsc;
+
+use super::Client;
+
+/// A request received by fake server
+#[derive(Debug)]
+pub(crate) struct Received {
+    pub path: String,
+    pub body: serde_json::Value,
+}
+
+type Respond = dyn Fn(&str) -> (StatusCode, serde_json::Value) + Send + Sync;
+
+/// Start a fake api server, `respond` returns status and `data` field of response for a path.
+///
+/// Non-200 status responds an empty body.
+pub(crate) fn serve<F>(respond: F) -> (Client, mpsc::UnboundedReceiver<Received>)
+where
+    F: Fn(&str) -> (StatusCode, serde_json::Value) + Send + Sync + 'static,
+{
+    let respond: Arc<Respond> = Arc::new(respond);
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let make_service = make_service_fn(move |_| {
+        let respond = Arc::clone(&respond);
+        let tx = tx.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let respond = Arc::clone(&respond);
+                let tx = tx.clone();
+                async move {
+                    let path = req.uri().path().to_string();
+                    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                    let body = serde_json::from_slice(&body).unwrap_or_default();
+                    let (status, data) = respond(&path);
+                    let _ = tx.send(Received { path, body });
+
+                    let body = if status == StatusCode::OK {
+                        serde_json::json!({ "code": 0, "message": "", "data": data }).to_string()
+                    } else {
+                        String::new()
+                    };
+
+                    let mut resp = Response::new(Body::from(body));
+                    *resp.status_mut() = status;
+                    Ok::<_, Infallible>(resp)
+                }
+            }))
+        }
+    });
+
+    let server = hyper::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = Client::new_from_bot_token("token")
+        .unwrap()
+        .with_base_url(format!("http://{}", addr));
+
+    (client, rx)
+}
@@ -2,10 +2,15 @@
 
 mod client;
 mod error;
+#[cfg(test)]
+pub(crate) mod fake;
+mod rate_limit;
 pub mod types;
 
 pub use client::Client;
-pub use error::Error;
+pub use error::{Error, ErrorCode};
+pub use rate_limit::BucketState;
+pub use reqwest::multipart;
 
 /// Result type for api module
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,277 @@
+//! rate limit accounting for kaiheila api
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use reqwest::header::HeaderMap;
+use tokio::time::Instant;
+
+static HEADER_LIMIT: &str = "X-Rate-Limit-Limit";
+static HEADER_REMAINING: &str = "X-Rate-Limit-Remaining";
+static HEADER_RESET: &str = "X-Rate-Limit-Reset";
+static HEADER_BUCKET: &str = "X-Rate-Limit-Bucket";
+static HEADER_GLOBAL: &str = "X-Rate-Limit-Global";
+
+/// wait time when server says too many requests but gives no reset time
+const DEFAULT_RESET: Duration = Duration::from_secs(1);
+
+/// Rate limit state of a bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketState {
+    /// max request count in a period, zero if server did not tell
+    pub limit: u64,
+    /// remaining request count in current period
+    pub remaining: u64,
+    /// when current period ends
+    pub reset_at: Instant,
+    /// length of a period, learnt from last reset header
+    window: Duration,
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterInner {
+    /// bucket name -> state
+    buckets: HashMap<String, BucketState>,
+    /// api path -> bucket name
+    paths: HashMap<String, String>,
+    /// all requests are blocked until this time
+    global_reset_at: Option<Instant>,
+}
+
+/// Per-bucket token accounting, learnt from rate limit headers of responses
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    inner: Mutex<RateLimiterInner>,
+}
+
+impl RateLimiter {
+    /// Wait until a request to `path` is allowed, and take a token from its bucket
+    pub async fn acquire(&self, path: &str) {
+        loop {
+            let wait_until = {
+                let mut inner = self.inner.lock().unwrap();
+                let now = Instant::now();
+
+                match inner.global_reset_at {
+                    Some(reset_at) if reset_at > now => Some(reset_at),
+                    _ => {
+                        inner.global_reset_at = None;
+
+                        let bucket = inner.paths.get(path).cloned();
+                        match bucket.as_deref().and_then(|b| inner.buckets.get_mut(b)) {
+                            Some(state) if state.reset_at <= now && state.limit == 0 => {
+                                // limit unknown, the bucket only blocks requests until the
+                                // reset of a 429 response, forget it
+                                inner.buckets.remove(bucket.as_deref().unwrap());
+                                inner.paths.remove(path);
+                                None
+                            }
+                            Some(state) if state.reset_at <= now => {
+                                // period ended, we don't know new state until next response,
+                                // assume a new full period started now
+                                state.remaining = state.limit.saturating_sub(1);
+                                state.reset_at = now + state.window;
+                                None
+                            }
+                            Some(state) if state.remaining == 0 => Some(state.reset_at),
+                            Some(state) => {
+                                state.remaining -= 1;
+                                None
+                            }
+                            None => None,
+                        }
+                    }
+                }
+            };
+
+            match wait_until {
+                Some(deadline) => {
+                    log::debug!(
+                        "Request {} is rate limited, wait {:?}",
+                        path,
+                        deadline - Instant::now()
+                    );
+                    tokio::time::sleep_until(deadline).await;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Update bucket state using response headers of a request to `path`.
+    ///
+    /// `limited` means the response status is 429 Too Many Requests.
+    pub fn update(&self, path: &str, headers: &HeaderMap, limited: bool) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let reset = header(HEADER_RESET)
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .map(Duration::from_secs_f64);
+
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+
+        if limited && header(HEADER_GLOBAL).is_some() {
+            let reset_at = now + reset.unwrap_or(DEFAULT_RESET);
+            log::warn!(
+                "Global rate limit reached, all requests blocked until {:?}",
+                reset_at
+            );
+            inner.global_reset_at.replace(reset_at);
+        }
+
+        let bucket = match header(HEADER_BUCKET) {
+            Some(bucket) => bucket.to_string(),
+            None => {
+                if !limited {
+                    return;
+                }
+                // no bucket info, limit this path alone
+                inner
+                    .paths
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_else(|| path.to_string())
+            }
+        };
+
+        inner.paths.insert(path.to_string(), bucket.clone());
+
+        let remaining = if limited {
+            Some(0)
+        } else {
+            header(HEADER_REMAINING).and_then(|v| v.parse().ok())
+        };
+
+        let state = inner.buckets.entry(bucket).or_insert(BucketState {
+            limit: 0,
+            remaining: 0,
+            reset_at: now,
+            window: DEFAULT_RESET,
+        });
+
+        if let Some(limit) = header(HEADER_LIMIT).and_then(|v| v.parse().ok()) {
+            state.limit = limit;
+        }
+        if let Some(remaining) = remaining {
+            state.remaining = remaining;
+        }
+        match reset {
+            Some(reset) => {
+                state.reset_at = now + reset;
+                if !reset.is_zero() {
+                    state.window = reset;
+                }
+            }
+            None if limited => state.reset_at = now + DEFAULT_RESET,
+            None => {}
+        }
+    }
+
+    /// Snapshot of all known bucket states
+    pub fn buckets(&self) -> HashMap<String, BucketState> {
+        self.inner.lock().unwrap().buckets.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, v.parse().unwrap());
+        }
+        map
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_wait_bucket_reset() {
+        let limiter = RateLimiter::default();
+
+        limiter.update(
+            "/message/create",
+            &headers(&[
+                (HEADER_LIMIT, "5"),
+                (HEADER_REMAINING, "1"),
+                (HEADER_RESET, "2"),
+                (HEADER_BUCKET, "message/create"),
+            ]),
+            false,
+        );
+
+        let start = Instant::now();
+        limiter.acquire("/message/create").await;
+        assert_eq!(Instant::now(), start);
+
+        limiter.acquire("/message/create").await;
+        assert_eq!(Instant::now() - start, Duration::from_secs(2));
+
+        let state = &limiter.buckets()["message/create"];
+        assert_eq!(state.limit, 5);
+        assert_eq!(state.remaining, 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_global() {
+        let limiter = RateLimiter::default();
+
+        limiter.update(
+            "/guild/list",
+            &headers(&[(HEADER_RESET, "3"), (HEADER_GLOBAL, "1")]),
+            true,
+        );
+
+        let start = Instant::now();
+        limiter.acquire("/channel/list").await;
+        assert_eq!(Instant::now() - start, Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_count_after_reset() {
+        let limiter = RateLimiter::default();
+
+        limiter.update(
+            "/message/create",
+            &headers(&[
+                (HEADER_LIMIT, "3"),
+                (HEADER_REMAINING, "0"),
+                (HEADER_RESET, "2"),
+                (HEADER_BUCKET, "message/create"),
+            ]),
+            false,
+        );
+
+        let start = Instant::now();
+        for remaining in [2, 1, 0] {
+            limiter.acquire("/message/create").await;
+            assert_eq!(Instant::now() - start, Duration::from_secs(2));
+            assert_eq!(limiter.buckets()["message/create"].remaining, remaining);
+        }
+
+        // no response updated the bucket, the assumed period still applies
+        limiter.acquire("/message/create").await;
+        assert_eq!(Instant::now() - start, Duration::from_secs(4));
+        assert_eq!(limiter.buckets()["message/create"].remaining, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_forget_path_bucket() {
+        let limiter = RateLimiter::default();
+
+        // too many requests, but no bucket info
+        limiter.update("/guild/list", &headers(&[(HEADER_RESET, "1")]), true);
+
+        let start = Instant::now();
+        limiter.acquire("/guild/list").await;
+        assert_eq!(Instant::now() - start, Duration::from_secs(1));
+        assert!(limiter.buckets().is_empty());
+
+        for _ in 0..5 {
+            limiter.update("/guild/list", &HeaderMap::new(), false);
+            limiter.acquire("/guild/list").await;
+        }
+        assert_eq!(Instant::now() - start, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use serde::Deserialize;
+use tokio::io::AsyncRead;
+
+/// A file to upload by api /asset/create
+pub struct Asset {
+    pub(crate) name: Option<String>,
+    pub(crate) source: AssetSource,
+}
+
+pub(crate) enum AssetSource {
+    Path(PathBuf),
+    Bytes(Bytes),
+    Reader(Box<dyn AsyncRead + Send + Sync + Unpin>),
+}
+
+impl std::fmt::Debug for Asset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self.source {
+            AssetSource::Path(ref path) => format!("Path({})", path.display()),
+            AssetSource::Bytes(ref bytes) => format!("Bytes({} bytes)", bytes.len()),
+            AssetSource::Reader(_) => "Reader".to_string(),
+        };
+
+        f.debug_struct("Asset")
+            .field("name", &self.name)
+            .field("source", &source)
+            .finish()
+    }
+}
+
+impl Asset {
+    /// Upload a local file, it's streamed when uploading instead of read into memory.
+    ///
+    /// The file name is used as asset name by default.
+    pub fn path<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            name: None,
+            source: AssetSource::Path(path.into()),
+        }
+    }
+
+    /// Upload a byte buffer with file name
+    pub fn bytes<N: Into<String>, B: Into<Bytes>>(name: N, bytes: B) -> Self {
+        Self {
+            name: Some(name.into()),
+            source: AssetSource::Bytes(bytes.into()),
+        }
+    }
+
+    /// Upload data read from an async reader with file name, it's streamed when uploading
+    pub fn reader<N: Into<String>, R: AsyncRead + Send + Sync + Unpin + 'static>(
+        name: N,
+        reader: R,
+    ) -> Self {
+        Self {
+            name: Some(name.into()),
+            source: AssetSource::Reader(Box::new(reader)),
+        }
+    }
+
+    /// Set file name of the asset, server may use it to guess file type
+    pub fn name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name.replace(name.into());
+        self
+    }
+}
+
+/// Response data of api /asset/create
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetData {
+    /// hosted url of the asset
+    pub url: String,
+}
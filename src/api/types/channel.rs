@@ -0,0 +1,289 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{serde_helper::int_bool, Page, Permissions, User};
+
+/// Kaiheila guild channel object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Channel {
+    /// channel id
+    pub id: String,
+    /// channel name
+    pub name: String,
+    /// creator id
+    #[serde(default)]
+    pub user_id: String,
+    /// guild id
+    #[serde(default)]
+    pub guild_id: String,
+    /// channel topic
+    #[serde(default)]
+    pub topic: String,
+    /// if this channel is a category
+    #[serde(default, with = "int_bool")]
+    pub is_category: bool,
+    /// parent category id
+    #[serde(default)]
+    pub parent_id: String,
+    /// sort level
+    #[serde(default)]
+    pub level: i64,
+    /// slow mode interval in milliseconds, zero for disabled
+    #[serde(default)]
+    pub slow_mode: u64,
+    /// channel type, 1 for text and 2 for voice, see [`ChannelKind`]
+    #[serde(rename = "type", default)]
+    pub kind: u8,
+    /// permission overwrites for roles
+    #[serde(default)]
+    pub permission_overwrites: Vec<RoleOverwrite>,
+    /// permission overwrites for users
+    #[serde(default)]
+    pub permission_users: Vec<UserOverwrite>,
+    /// if permissions are synced with parent category
+    #[serde(default, with = "int_bool")]
+    pub permission_sync: bool,
+}
+
+/// Channel permission overwrite for a role
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleOverwrite {
+    /// role id
+    pub role_id: u64,
+    /// allowed permissions
+    pub allow: Permissions,
+    /// denied permissions
+    pub deny: Permissions,
+}
+
+/// Channel permission overwrite for a user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserOverwrite {
+    /// the user
+    pub user: User,
+    /// allowed permissions
+    pub allow: Permissions,
+    /// denied permissions
+    pub deny: Permissions,
+}
+
+/// Channel type used when create or list channels
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum ChannelKind {
+    /// text channel
+    Text,
+    /// voice channel
+    Voice,
+}
+
+/// Error when convert a number into [`ChannelKind`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownChannelKind(pub u8);
+
+impl Display for UnknownChannelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown channel type {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownChannelKind {}
+
+impl TryFrom<u8> for ChannelKind {
+    type Error = UnknownChannelKind;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Text),
+            2 => Ok(Self::Voice),
+            _ => Err(UnknownChannelKind(value)),
+        }
+    }
+}
+
+impl From<ChannelKind> for u8 {
+    fn from(kind: ChannelKind) -> Self {
+        match kind {
+            ChannelKind::Text => 1,
+            ChannelKind::Voice => 2,
+        }
+    }
+}
+
+/// Response data of api /channel/list
+pub type ChannelList = Page<Channel>;
+
+/// Request body of api /channel/create
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateChannel {
+    /// guild id
+    pub guild_id: String,
+    /// channel name
+    pub name: String,
+    /// channel type, text channel if not set
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ChannelKind>,
+    /// parent category id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// user limit of voice channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_amount: Option<u32>,
+    /// voice quality of voice channel, 1 for fluent, 2 for normal, 3 for high
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_quality: Option<u8>,
+}
+
+impl CreateChannel {
+    /// Create a new text channel
+    pub fn new<G: Into<String>, N: Into<String>>(guild_id: G, name: N) -> Self {
+        Self {
+            guild_id: guild_id.into(),
+            name: name.into(),
+            kind: None,
+            parent_id: None,
+            limit_amount: None,
+            voice_quality: None,
+        }
+    }
+
+    /// Set channel type
+    pub fn kind(mut self, kind: ChannelKind) -> Self {
+        self.kind.replace(kind);
+        self
+    }
+
+    /// Put the channel in category
+    pub fn parent<S: Into<String>>(mut self, parent_id: S) -> Self {
+        self.parent_id.replace(parent_id.into());
+        self
+    }
+
+    /// Set user limit of voice channel
+    pub fn limit_amount(mut self, amount: u32) -> Self {
+        self.limit_amount.replace(amount);
+        self
+    }
+
+    /// Set voice quality of voice channel
+    pub fn voice_quality(mut self, quality: u8) -> Self {
+        self.voice_quality.replace(quality);
+        self
+    }
+}
+
+/// Request body of api /channel/update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateChannel {
+    /// channel id
+    pub channel_id: String,
+    /// new name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// new topic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// new slow mode interval in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slow_mode: Option<u64>,
+}
+
+impl UpdateChannel {
+    /// Update channel, nothing is changed by default
+    pub fn new<S: Into<String>>(channel_id: S) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            name: None,
+            topic: None,
+            slow_mode: None,
+        }
+    }
+
+    /// Set new name
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name.replace(name.into());
+        self
+    }
+
+    /// Set new topic
+    pub fn topic<S: Into<String>>(mut self, topic: S) -> Self {
+        self.topic.replace(topic.into());
+        self
+    }
+
+    /// Set new slow mode interval in milliseconds
+    pub fn slow_mode(mut self, slow_mode: u64) -> Self {
+        self.slow_mode.replace(slow_mode);
+        self
+    }
+}
+
+/// Response data of api /channel-role/index
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelPermissions {
+    /// permission overwrites for roles
+    #[serde(default)]
+    pub permission_overwrites: Vec<RoleOverwrite>,
+    /// permission overwrites for users
+    #[serde(default)]
+    pub permission_users: Vec<UserOverwrite>,
+    /// if permissions are synced with parent category
+    #[serde(default, with = "int_bool")]
+    pub permission_sync: bool,
+}
+
+/// Target of a channel permission overwrite
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverwriteTarget {
+    /// a role
+    Role(u64),
+    /// a user
+    User(String),
+}
+
+impl Serialize for OverwriteTarget {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        match self {
+            Self::Role(id) => {
+                map.serialize_entry("type", "role_id")?;
+                map.serialize_entry("value", &id.to_string())?;
+            }
+            Self::User(id) => {
+                map.serialize_entry("type", "user_id")?;
+                map.serialize_entry("value", id)?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Response data of api /channel-role/create and /channel-role/update
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelOverwrite {
+    /// allowed permissions
+    pub allow: Permissions,
+    /// denied permissions
+    pub deny: Permissions,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_overwrite_target_serialize() {
+        assert_eq!(
+            serde_json::to_value(OverwriteTarget::Role(42)).unwrap(),
+            json!({ "type": "role_id", "value": "42" })
+        );
+        assert_eq!(
+            serde_json::to_value(OverwriteTarget::User("1234".to_string())).unwrap(),
+            json!({ "type": "user_id", "value": "1234" })
+        );
+    }
+}
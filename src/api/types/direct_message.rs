@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ListFlag, MessageReaction, Page, Quote, User};
+use crate::ws::event::{Attachment, MessageType};
+
+/// How to address a private chat
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatTarget {
+    /// by private chat code
+    ChatCode(String),
+    /// by the other user's id
+    TargetId(String),
+}
+
+/// Private chat session
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserChat {
+    /// private chat code
+    pub code: String,
+    /// last read time, unix timestamp in milliseconds
+    #[serde(default)]
+    pub last_read_time: u64,
+    /// latest message time, unix timestamp in milliseconds
+    #[serde(default)]
+    pub latest_msg_time: u64,
+    /// unread message count
+    #[serde(default)]
+    pub unread_count: u64,
+    /// the other user
+    pub target_info: ChatTargetInfo,
+}
+
+/// The other user of a private chat
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatTargetInfo {
+    /// user id
+    pub id: String,
+    /// user name
+    pub username: String,
+    /// if user is online
+    #[serde(default)]
+    pub online: bool,
+    /// avatar url
+    #[serde(default)]
+    pub avatar: String,
+}
+
+/// Response data of api /user-chat/list
+pub type UserChatList = Page<UserChat>;
+
+/// Request body of api /direct-message/create
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateDirectMessage {
+    /// message type, server treats it as KMarkdown if not set
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<MessageType>,
+    /// private chat to send message to
+    #[serde(flatten)]
+    pub target: ChatTarget,
+    /// message content
+    pub content: String,
+    /// id of the message to quote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    /// random string, will be returned in message event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+impl CreateDirectMessage {
+    /// Create a new private message
+    pub fn new<C: Into<String>>(target: ChatTarget, content: C) -> Self {
+        Self {
+            message_type: None,
+            target,
+            content: content.into(),
+            quote: None,
+            nonce: None,
+        }
+    }
+
+    /// Set message type
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type.replace(message_type);
+        self
+    }
+
+    /// Quote(reply to) a message
+    pub fn quote<S: Into<String>>(mut self, msg_id: S) -> Self {
+        self.quote.replace(msg_id.into());
+        self
+    }
+
+    /// Set nonce
+    pub fn nonce<S: Into<String>>(mut self, nonce: S) -> Self {
+        self.nonce.replace(nonce.into());
+        self
+    }
+}
+
+/// Request body of api /direct-message/update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateDirectMessage {
+    /// message id
+    pub msg_id: String,
+    /// new content
+    pub content: String,
+    /// new quoted message id, empty string to remove quote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+}
+
+impl UpdateDirectMessage {
+    /// Update content of a private message
+    pub fn new<M: Into<String>, C: Into<String>>(msg_id: M, content: C) -> Self {
+        Self {
+            msg_id: msg_id.into(),
+            content: content.into(),
+            quote: None,
+        }
+    }
+
+    /// Set quoted message, empty string to remove quote
+    pub fn quote<S: Into<String>>(mut self, msg_id: S) -> Self {
+        self.quote.replace(msg_id.into());
+        self
+    }
+}
+
+/// Query of api /direct-message/list
+#[derive(Debug, Clone, Serialize)]
+pub struct ListDirectMessages {
+    /// private chat
+    #[serde(flatten)]
+    pub target: ChatTarget,
+    /// reference message id, the latest message is used if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msg_id: Option<String>,
+    /// list direction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<ListFlag>,
+    /// result count, default is 50
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+impl ListDirectMessages {
+    /// List latest messages in private chat
+    pub fn new(target: ChatTarget) -> Self {
+        Self {
+            target,
+            msg_id: None,
+            flag: None,
+            page_size: None,
+        }
+    }
+
+    /// List messages relative to given message
+    pub fn around<S: Into<String>>(mut self, msg_id: S, flag: ListFlag) -> Self {
+        self.msg_id.replace(msg_id.into());
+        self.flag.replace(flag);
+        self
+    }
+
+    /// Set result count
+    pub fn page_size(mut self, size: u32) -> Self {
+        self.page_size.replace(size);
+        self
+    }
+}
+
+/// Response data of api /direct-message/list
+#[derive(Debug, Clone, Deserialize)]
+pub struct DirectMessageList {
+    /// messages
+    pub items: Vec<DirectMessage>,
+}
+
+/// Private message detail
+#[derive(Debug, Clone, Deserialize)]
+pub struct DirectMessage {
+    /// message id
+    pub id: String,
+    /// message type, see [`MessageType`], kept as number so new types don't break decoding
+    #[serde(rename = "type")]
+    pub message_type: u8,
+    /// sender user id
+    pub author_id: String,
+    /// message content
+    pub content: String,
+    /// attachment of media message
+    #[serde(default)]
+    pub attachments: Option<Attachment>,
+    /// reactions of this message
+    #[serde(default)]
+    pub reactions: Vec<MessageReaction>,
+    /// quoted message
+    #[serde(default)]
+    pub quote: Option<Quote>,
+    /// if the message is read by receiver
+    #[serde(default)]
+    pub read_status: bool,
+    /// create time, unix timestamp in milliseconds
+    pub create_at: u64,
+    /// last update time, unix timestamp in milliseconds, zero if never updated
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// User who reacted to a message
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionUser {
+    /// the user
+    #[serde(flatten)]
+    pub user: User,
+    /// reaction time, unix timestamp in milliseconds
+    #[serde(default)]
+    pub reaction_time: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_list_direct_messages_query() {
+        let query = ListDirectMessages::new(ChatTarget::ChatCode("code".to_string()))
+            .around("msg", ListFlag::Before);
+
+        let req = reqwest::Client::new()
+            .get("https://example.com/")
+            .query(&query)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            req.url().query(),
+            Some("chat_code=code&msg_id=msg&flag=before")
+        );
+    }
+
+    #[test]
+    fn test_direct_message_list_with_unknown_type() {
+        let list: DirectMessageList = serde_json::from_value(json!({
+            "items": [
+                { "id": "a", "type": 9, "author_id": "1", "content": "hi", "create_at": 1 },
+                { "id": "b", "type": 99, "author_id": "1", "content": "?", "create_at": 2 },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            MessageType::try_from(list.items[0].message_type),
+            Ok(MessageType::KMarkdown)
+        );
+        assert!(MessageType::try_from(list.items[1].message_type).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{serde_helper::int_bool, Channel, Page, PageMeta, Role, User};
+
+/// Kaiheila guild object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guild {
+    /// guild id
+    pub id: String,
+    /// guild name
+    pub name: String,
+    /// guild topic
+    #[serde(default)]
+    pub topic: String,
+    /// guild owner id
+    #[serde(default)]
+    pub user_id: String,
+    /// icon url
+    #[serde(default)]
+    pub icon: String,
+    /// notify type, 0 for server default, 1 for all, 2 for mention only, 3 for none
+    #[serde(default)]
+    pub notify_type: i64,
+    /// voice server region
+    #[serde(default)]
+    pub region: String,
+    /// if the guild is public
+    #[serde(default, with = "int_bool")]
+    pub enable_open: bool,
+    /// default channel id
+    #[serde(default)]
+    pub default_channel_id: String,
+    /// welcome channel id
+    #[serde(default)]
+    pub welcome_channel_id: String,
+}
+
+/// Response data of api /guild/list
+pub type GuildList = Page<Guild>;
+
+/// Response data of api /guild/view
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildDetail {
+    /// guild info
+    #[serde(flatten)]
+    pub guild: Guild,
+    /// roles of the guild
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    /// channels of the guild
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+}
+
+/// Query of api /guild/user-list
+#[derive(Debug, Clone, Serialize)]
+pub struct ListGuildMembers {
+    /// guild id
+    pub guild_id: String,
+    /// only list users in this channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    /// search by user name or nickname
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// only list users of this role
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_id: Option<u64>,
+    /// only list users whose mobile is verified(1) or not(0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mobile_verified: Option<u8>,
+    /// sort by active time, 0 for descending, 1 for ascending
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_time: Option<u8>,
+    /// sort by join time, 0 for descending, 1 for ascending
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<u8>,
+    /// only get this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_user_id: Option<String>,
+}
+
+impl ListGuildMembers {
+    /// List all members of guild
+    pub fn new<S: Into<String>>(guild_id: S) -> Self {
+        Self {
+            guild_id: guild_id.into(),
+            channel_id: None,
+            search: None,
+            role_id: None,
+            mobile_verified: None,
+            active_time: None,
+            joined_at: None,
+            filter_user_id: None,
+        }
+    }
+
+    /// Only list users in this channel
+    pub fn channel<S: Into<String>>(mut self, channel_id: S) -> Self {
+        self.channel_id.replace(channel_id.into());
+        self
+    }
+
+    /// Search by user name or nickname
+    pub fn search<S: Into<String>>(mut self, keyword: S) -> Self {
+        self.search.replace(keyword.into());
+        self
+    }
+
+    /// Only list users of this role
+    pub fn role(mut self, role_id: u64) -> Self {
+        self.role_id.replace(role_id);
+        self
+    }
+
+    /// Only list users whose mobile is verified or not
+    pub fn mobile_verified(mut self, verified: bool) -> Self {
+        self.mobile_verified.replace(u8::from(verified));
+        self
+    }
+
+    /// Sort by active time
+    pub fn sort_by_active_time(mut self, ascending: bool) -> Self {
+        self.active_time.replace(u8::from(ascending));
+        self
+    }
+
+    /// Sort by join time
+    pub fn sort_by_joined_at(mut self, ascending: bool) -> Self {
+        self.joined_at.replace(u8::from(ascending));
+        self
+    }
+
+    /// Only get this user
+    pub fn user<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.filter_user_id.replace(user_id.into());
+        self
+    }
+}
+
+/// Guild member
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMember {
+    /// user info
+    #[serde(flatten)]
+    pub user: User,
+    /// join time, unix timestamp in milliseconds
+    #[serde(default)]
+    pub joined_at: u64,
+    /// last active time, unix timestamp in milliseconds
+    #[serde(default)]
+    pub active_time: u64,
+}
+
+/// Response data of api /guild/user-list
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMemberList {
+    /// members
+    pub items: Vec<GuildMember>,
+    /// pagination info
+    #[serde(default)]
+    pub meta: PageMeta,
+    /// total user count
+    #[serde(default)]
+    pub user_count: u64,
+    /// online user count
+    #[serde(default)]
+    pub online_count: u64,
+    /// offline user count
+    #[serde(default)]
+    pub offline_count: u64,
+}
+
+/// Mute type of guild member
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum MuteType {
+    /// can't speak in voice channel
+    Mic,
+    /// can't hear in voice channel
+    Headset,
+}
+
+/// Error when convert a number into [`MuteType`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMuteType(pub u8);
+
+impl Display for UnknownMuteType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown mute type {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMuteType {}
+
+impl TryFrom<u8> for MuteType {
+    type Error = UnknownMuteType;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Mic),
+            2 => Ok(Self::Headset),
+            _ => Err(UnknownMuteType(value)),
+        }
+    }
+}
+
+impl From<MuteType> for u8 {
+    fn from(t: MuteType) -> Self {
+        match t {
+            MuteType::Mic => 1,
+            MuteType::Headset => 2,
+        }
+    }
+}
+
+/// Muted users of a mute type
+#[derive(Debug, Clone, Deserialize)]
+pub struct MutedUsers {
+    /// mute type
+    #[serde(rename = "type")]
+    pub mute_type: MuteType,
+    /// muted user ids
+    #[serde(default)]
+    pub user_ids: Vec<String>,
+}
+
+/// Response data of api /guild-mute/list
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMuteList {
+    /// users can't speak
+    pub mic: MutedUsers,
+    /// users can't hear
+    pub headset: MutedUsers,
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+
+use super::User;
+use crate::ws::event::{Attachment, Emoji, MessageType};
+
+/// Request body of api /message/create
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateMessage {
+    /// message type, server treats it as KMarkdown if not set
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<MessageType>,
+    /// target channel id
+    pub target_id: String,
+    /// message content
+    pub content: String,
+    /// id of the message to quote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    /// random string, will be returned in message event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// if set, message is only visible to this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_target_id: Option<String>,
+}
+
+impl CreateMessage {
+    /// Create a new message to target channel
+    pub fn new<T: Into<String>, C: Into<String>>(target_id: T, content: C) -> Self {
+        Self {
+            message_type: None,
+            target_id: target_id.into(),
+            content: content.into(),
+            quote: None,
+            nonce: None,
+            temp_target_id: None,
+        }
+    }
+
+    /// Set message type
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type.replace(message_type);
+        self
+    }
+
+    /// Quote(reply to) a message
+    pub fn quote<S: Into<String>>(mut self, msg_id: S) -> Self {
+        self.quote.replace(msg_id.into());
+        self
+    }
+
+    /// Set nonce
+    pub fn nonce<S: Into<String>>(mut self, nonce: S) -> Self {
+        self.nonce.replace(nonce.into());
+        self
+    }
+
+    /// Make message only visible to given user
+    pub fn temp_target_id<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.temp_target_id.replace(user_id.into());
+        self
+    }
+}
+
+/// Response data of api /message/create
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatedMessage {
+    /// created message id
+    pub msg_id: String,
+    /// create time, unix timestamp in milliseconds
+    pub msg_timestamp: u64,
+    /// nonce given in request
+    #[serde(default)]
+    pub nonce: String,
+}
+
+/// Request body of api /message/update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateMessage {
+    /// message id
+    pub msg_id: String,
+    /// new content
+    pub content: String,
+    /// new quoted message id, empty string to remove quote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    /// only update the message visible to this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_target_id: Option<String>,
+}
+
+impl UpdateMessage {
+    /// Update content of a message
+    pub fn new<M: Into<String>, C: Into<String>>(msg_id: M, content: C) -> Self {
+        Self {
+            msg_id: msg_id.into(),
+            content: content.into(),
+            quote: None,
+            temp_target_id: None,
+        }
+    }
+
+    /// Set quoted message, empty string to remove quote
+    pub fn quote<S: Into<String>>(mut self, msg_id: S) -> Self {
+        self.quote.replace(msg_id.into());
+        self
+    }
+
+    /// Only update the message visible to given user
+    pub fn temp_target_id<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.temp_target_id.replace(user_id.into());
+        self
+    }
+}
+
+/// Direction of api /message/list, relative to the reference message
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFlag {
+    /// messages before reference message
+    Before,
+    /// messages around reference message
+    Around,
+    /// messages after reference message
+    After,
+}
+
+/// Query of api /message/list
+#[derive(Debug, Clone, Serialize)]
+pub struct ListMessages {
+    /// channel id
+    pub target_id: String,
+    /// reference message id, the latest message is used if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msg_id: Option<String>,
+    /// only list pinned messages, 0 or 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<u8>,
+    /// list direction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<ListFlag>,
+    /// result count, default is 50
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+impl ListMessages {
+    /// List latest messages in channel
+    pub fn new<S: Into<String>>(target_id: S) -> Self {
+        Self {
+            target_id: target_id.into(),
+            msg_id: None,
+            pin: None,
+            flag: None,
+            page_size: None,
+        }
+    }
+
+    /// List messages relative to given message
+    pub fn around<S: Into<String>>(mut self, msg_id: S, flag: ListFlag) -> Self {
+        self.msg_id.replace(msg_id.into());
+        self.flag.replace(flag);
+        self
+    }
+
+    /// Only list pinned messages
+    pub fn pinned(mut self) -> Self {
+        self.pin.replace(1);
+        self
+    }
+
+    /// Set result count
+    pub fn page_size(mut self, size: u32) -> Self {
+        self.page_size.replace(size);
+        self
+    }
+}
+
+/// Response data of api /message/list
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageList {
+    /// messages
+    pub items: Vec<ChannelMessage>,
+}
+
+/// Message detail returned by message apis
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelMessage {
+    /// message id
+    pub id: String,
+    /// message type, see [`MessageType`], kept as number so new types don't break decoding
+    #[serde(rename = "type")]
+    pub message_type: u8,
+    /// message sender
+    pub author: User,
+    /// message content
+    pub content: String,
+    /// mentioned user ids
+    #[serde(default)]
+    pub mention: Vec<String>,
+    /// if mentioned all users
+    #[serde(default)]
+    pub mention_all: bool,
+    /// mentioned role ids
+    #[serde(default)]
+    pub mention_roles: Vec<u64>,
+    /// if mentioned online users
+    #[serde(default)]
+    pub mention_here: bool,
+    /// attachment of media message
+    #[serde(default)]
+    pub attachments: Option<Attachment>,
+    /// reactions of this message
+    #[serde(default)]
+    pub reactions: Vec<MessageReaction>,
+    /// quoted message
+    #[serde(default)]
+    pub quote: Option<Quote>,
+    /// create time, unix timestamp in milliseconds
+    pub create_at: u64,
+    /// last update time, unix timestamp in milliseconds, zero if never updated
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// Reaction summary of a message
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReaction {
+    /// the emoji
+    pub emoji: Emoji,
+    /// reaction count
+    pub count: u64,
+    /// if bot itself reacted
+    pub me: bool,
+}
+
+/// Quoted message
+#[derive(Debug, Clone, Deserialize)]
+pub struct Quote {
+    /// quoted message id
+    pub id: String,
+    /// quoted message type, see [`MessageType`]
+    #[serde(rename = "type")]
+    pub message_type: u8,
+    /// quoted message content
+    pub content: String,
+    /// quoted message create time, unix timestamp in milliseconds
+    pub create_at: u64,
+    /// quoted message sender
+    pub author: User,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_create_message_serialize() {
+        let message = CreateMessage::new("channel", "hello")
+            .message_type(MessageType::KMarkdown)
+            .quote("quoted");
+
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            json!({
+                "type": 9,
+                "target_id": "channel",
+                "content": "hello",
+                "quote": "quoted",
+            })
+        );
+    }
+
+    #[test]
+    fn test_message_list_with_unknown_type() {
+        let author = json!({ "id": "1", "username": "someone" });
+        let list: MessageList = serde_json::from_value(json!({
+            "items": [
+                { "id": "a", "type": 1, "author": author, "content": "hi", "create_at": 1 },
+                { "id": "b", "type": 99, "author": author, "content": "?", "create_at": 2 },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            MessageType::try_from(list.items[0].message_type),
+            Ok(MessageType::Text)
+        );
+        assert!(MessageType::try_from(list.items[1].message_type).is_err());
+    }
+}
@@ -1,5 +1,39 @@
 //! Kaiheila HTTP API response types
 
+mod asset;
+mod channel;
+mod direct_message;
+mod guild;
+mod message;
+mod page;
+mod permission;
+mod role;
+mod serde_helper;
+mod user;
+
+pub(crate) use asset::AssetSource;
+pub use asset::{Asset, AssetData};
+pub use channel::{
+    Channel, ChannelKind, ChannelList, ChannelOverwrite, ChannelPermissions, CreateChannel,
+    OverwriteTarget, RoleOverwrite, UnknownChannelKind, UpdateChannel, UserOverwrite,
+};
+pub use direct_message::{
+    ChatTarget, ChatTargetInfo, CreateDirectMessage, DirectMessage, DirectMessageList,
+    ListDirectMessages, ReactionUser, UpdateDirectMessage, UserChat, UserChatList,
+};
+pub use guild::{
+    Guild, GuildDetail, GuildList, GuildMember, GuildMemberList, GuildMuteList, ListGuildMembers,
+    MuteType, MutedUsers, UnknownMuteType,
+};
+pub use message::{
+    ChannelMessage, CreateMessage, CreatedMessage, ListFlag, ListMessages, MessageList,
+    MessageReaction, Quote, UpdateMessage,
+};
+pub use page::{Page, PageMeta};
+pub use permission::{Permissions, EVERYONE_ROLE_ID};
+pub use role::{Role, RoleGrant, RoleList, UpdateRole};
+pub use user::User;
+
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use serde::Deserialize;
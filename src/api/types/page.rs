@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+/// Pagination info of list apis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct PageMeta {
+    /// current page, starts from 1
+    #[serde(default)]
+    pub page: u32,
+    /// total page count
+    #[serde(default)]
+    pub page_total: u32,
+    /// item count per page
+    #[serde(default)]
+    pub page_size: u32,
+    /// total item count
+    #[serde(default)]
+    pub total: u64,
+}
+
+impl PageMeta {
+    /// if there are more pages after this one
+    pub fn has_next(&self) -> bool {
+        self.page < self.page_total
+    }
+}
+
+/// One page of a paginated list api response
+#[derive(Debug, Clone, Deserialize)]
+pub struct Page<T> {
+    /// items in this page
+    pub items: Vec<T>,
+    /// pagination info
+    #[serde(default)]
+    pub meta: PageMeta,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_page_decode() {
+        let page: Page<u32> = serde_json::from_value(json!({
+            "items": [1, 2],
+            "meta": { "page": 1, "page_total": 3, "page_size": 2, "total": 5 },
+            "sort": {},
+        }))
+        .unwrap();
+
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.meta.has_next());
+
+        let page: Page<u32> = serde_json::from_value(json!({ "items": [] })).unwrap();
+        assert!(!page.meta.has_next());
+    }
+}
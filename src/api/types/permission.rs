@@ -0,0 +1,220 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Role, RoleOverwrite, UserOverwrite};
+
+bitflags::bitflags! {
+    /// Kaiheila permission bits, used in roles and channel permission overwrites.
+    ///
+    /// See <https://developer.kaiheila.cn/doc/http/guild-role> for details.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+    pub struct Permissions: u64 {
+        /// administrator, has all permissions
+        const ADMIN = 1 << 0;
+        /// manage guild
+        const MANAGE_GUILD = 1 << 1;
+        /// view audit log
+        const VIEW_AUDIT_LOG = 1 << 2;
+        /// create invite
+        const CREATE_INVITE = 1 << 3;
+        /// manage invite
+        const MANAGE_INVITE = 1 << 4;
+        /// manage channel
+        const MANAGE_CHANNEL = 1 << 5;
+        /// kick user
+        const KICK_USER = 1 << 6;
+        /// ban user
+        const BAN_USER = 1 << 7;
+        /// manage custom emoji
+        const MANAGE_EMOJI = 1 << 8;
+        /// change own nickname
+        const CHANGE_NICKNAME = 1 << 9;
+        /// manage roles
+        const MANAGE_ROLE = 1 << 10;
+        /// view text and voice channels
+        const VIEW_CHANNEL = 1 << 11;
+        /// send message
+        const SEND_MESSAGE = 1 << 12;
+        /// manage message
+        const MANAGE_MESSAGE = 1 << 13;
+        /// upload file
+        const UPLOAD_FILE = 1 << 14;
+        /// connect voice channel
+        const CONNECT_VOICE = 1 << 15;
+        /// manage voice channel
+        const MANAGE_VOICE = 1 << 16;
+        /// mention @all
+        const MENTION_ALL = 1 << 17;
+        /// add reaction
+        const ADD_REACTION = 1 << 18;
+        /// follow existing reaction
+        const FOLLOW_REACTION = 1 << 19;
+        /// be moved into voice channel passively
+        const PASSIVE_CONNECT_VOICE = 1 << 20;
+        /// only speak by push-to-talk
+        const SPEAK_ONLY_BY_KEY = 1 << 21;
+        /// speak freely
+        const FREE_SPEAK = 1 << 22;
+        /// speak
+        const SPEAK = 1 << 23;
+        /// deafen other users
+        const DEAFEN_USER = 1 << 24;
+        /// mute other users
+        const MUTE_USER = 1 << 25;
+        /// change nickname of others
+        const MANAGE_NICKNAME = 1 << 26;
+        /// play music
+        const PLAY_MUSIC = 1 << 27;
+    }
+}
+
+impl Serialize for Permissions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Permissions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Self::from_bits_retain)
+    }
+}
+
+/// The everyone role id, every member has it implicitly
+pub const EVERYONE_ROLE_ID: u64 = 0;
+
+impl Permissions {
+    /// Calculate guild level permissions of a member from guild roles and member's role ids.
+    ///
+    /// The everyone role is always included, and administrator gets all permissions.
+    pub fn of_member(guild_roles: &[Role], member_roles: &[u64]) -> Self {
+        let permissions = guild_roles
+            .iter()
+            .filter(|role| role.role_id == EVERYONE_ROLE_ID || member_roles.contains(&role.role_id))
+            .fold(Self::empty(), |acc, role| acc | role.permissions);
+
+        if permissions.contains(Self::ADMIN) {
+            Self::all()
+        } else {
+            permissions
+        }
+    }
+
+    /// Calculate effective permissions of a member in a channel.
+    ///
+    /// Overwrites are applied in order: everyone role, member's roles(merged), then the user.
+    /// Administrator is not affected by any overwrite.
+    pub fn of_member_in_channel(
+        guild_roles: &[Role],
+        member_roles: &[u64],
+        user_id: &str,
+        role_overwrites: &[RoleOverwrite],
+        user_overwrites: &[UserOverwrite],
+    ) -> Self {
+        let mut permissions = Self::of_member(guild_roles, member_roles);
+        if permissions.contains(Self::ADMIN) {
+            return permissions;
+        }
+
+        if let Some(everyone) = role_overwrites
+            .iter()
+            .find(|o| o.role_id == EVERYONE_ROLE_ID)
+        {
+            permissions = permissions.overwrite(everyone.allow, everyone.deny);
+        }
+
+        let (allow, deny) = role_overwrites
+            .iter()
+            .filter(|o| o.role_id != EVERYONE_ROLE_ID && member_roles.contains(&o.role_id))
+            .fold((Self::empty(), Self::empty()), |(allow, deny), o| {
+                (allow | o.allow, deny | o.deny)
+            });
+        permissions = permissions.overwrite(allow, deny);
+
+        if let Some(user) = user_overwrites.iter().find(|o| o.user.id == user_id) {
+            permissions = permissions.overwrite(user.allow, user.deny);
+        }
+
+        permissions
+    }
+
+    fn overwrite(self, allow: Self, deny: Self) -> Self {
+        (self - deny) | allow
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn role(role_id: u64, permissions: Permissions) -> Role {
+        Role {
+            role_id,
+            name: format!("role {}", role_id),
+            color: 0,
+            position: 0,
+            hoist: false,
+            mentionable: false,
+            permissions,
+        }
+    }
+
+    fn role_overwrite(role_id: u64, allow: Permissions, deny: Permissions) -> RoleOverwrite {
+        RoleOverwrite {
+            role_id,
+            allow,
+            deny,
+        }
+    }
+
+    #[test]
+    fn test_permissions_serde() {
+        let p: Permissions = serde_json::from_value(json!(4096 | 8192)).unwrap();
+        assert_eq!(p, Permissions::SEND_MESSAGE | Permissions::MANAGE_MESSAGE);
+        assert_eq!(serde_json::to_value(p).unwrap(), json!(12288));
+    }
+
+    #[test]
+    fn test_permissions_of_member() {
+        let roles = [
+            role(EVERYONE_ROLE_ID, Permissions::VIEW_CHANNEL),
+            role(1, Permissions::SEND_MESSAGE),
+            role(2, Permissions::ADMIN),
+        ];
+
+        assert_eq!(
+            Permissions::of_member(&roles, &[1]),
+            Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGE
+        );
+        assert_eq!(Permissions::of_member(&roles, &[2]), Permissions::all());
+    }
+
+    #[test]
+    fn test_permissions_in_channel() {
+        let roles = [
+            role(
+                EVERYONE_ROLE_ID,
+                Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGE,
+            ),
+            role(1, Permissions::empty()),
+        ];
+
+        let role_overwrites = [
+            role_overwrite(
+                EVERYONE_ROLE_ID,
+                Permissions::empty(),
+                Permissions::SEND_MESSAGE,
+            ),
+            role_overwrite(1, Permissions::MANAGE_MESSAGE, Permissions::empty()),
+        ];
+
+        let member = Permissions::of_member_in_channel(&roles, &[], "u", &role_overwrites, &[]);
+        assert_eq!(member, Permissions::VIEW_CHANNEL);
+
+        let manager = Permissions::of_member_in_channel(&roles, &[1], "u", &role_overwrites, &[]);
+        assert_eq!(
+            manager,
+            Permissions::VIEW_CHANNEL | Permissions::MANAGE_MESSAGE
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use super::{serde_helper::int_bool, Page, Permissions};
+
+/// Kaiheila guild role object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Role {
+    /// role id
+    pub role_id: u64,
+    /// role name
+    pub name: String,
+    /// role color, as a `0xRRGGBB` integer
+    #[serde(default)]
+    pub color: u32,
+    /// role position, smaller is higher
+    #[serde(default)]
+    pub position: i64,
+    /// if members of this role are shown separately in member list
+    #[serde(default, with = "int_bool")]
+    pub hoist: bool,
+    /// if this role can be mentioned by everyone
+    #[serde(default, with = "int_bool")]
+    pub mentionable: bool,
+    /// permissions
+    #[serde(default)]
+    pub permissions: Permissions,
+}
+
+/// Response data of api /guild-role/list
+pub type RoleList = Page<Role>;
+
+/// Request body of api /guild-role/update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateRole {
+    /// guild id
+    pub guild_id: String,
+    /// role id
+    pub role_id: u64,
+    /// new name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// new color
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<u32>,
+    /// show separately or not, 0 or 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hoist: Option<u8>,
+    /// can be mentioned or not, 0 or 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mentionable: Option<u8>,
+    /// new permissions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Permissions>,
+}
+
+impl UpdateRole {
+    /// Update role, nothing is changed by default
+    pub fn new<S: Into<String>>(guild_id: S, role_id: u64) -> Self {
+        Self {
+            guild_id: guild_id.into(),
+            role_id,
+            name: None,
+            color: None,
+            hoist: None,
+            mentionable: None,
+            permissions: None,
+        }
+    }
+
+    /// Set new name
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name.replace(name.into());
+        self
+    }
+
+    /// Set new color, as a `0xRRGGBB` integer
+    pub fn color(mut self, color: u32) -> Self {
+        self.color.replace(color);
+        self
+    }
+
+    /// Set if members of this role are shown separately
+    pub fn hoist(mut self, hoist: bool) -> Self {
+        self.hoist.replace(u8::from(hoist));
+        self
+    }
+
+    /// Set if this role can be mentioned by everyone
+    pub fn mentionable(mut self, mentionable: bool) -> Self {
+        self.mentionable.replace(u8::from(mentionable));
+        self
+    }
+
+    /// Set new permissions
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions.replace(permissions);
+        self
+    }
+}
+
+/// Response data of api /guild-role/grant and /guild-role/revoke
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoleGrant {
+    /// user id
+    pub user_id: String,
+    /// guild id
+    pub guild_id: String,
+    /// all role ids of the user after operation
+    #[serde(default)]
+    pub roles: Vec<u64>,
+}
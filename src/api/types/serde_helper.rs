@@ -0,0 +1,29 @@
+//! serde helpers for kaiheila's loose json types
+
+/// (de)serialize a bool field which kaiheila may send as `0`/`1` integer
+pub(crate) mod int_bool {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum IntOrBool {
+        Int(i64),
+        Bool(bool),
+    }
+
+    pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(u8::from(*value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        match IntOrBool::deserialize(deserializer)? {
+            IntOrBool::Bool(b) => Ok(b),
+            IntOrBool::Int(0) => Ok(false),
+            IntOrBool::Int(1) => Ok(true),
+            IntOrBool::Int(i) => Err(de::Error::invalid_value(
+                de::Unexpected::Signed(i),
+                &"0 or 1",
+            )),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Kaiheila user object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    /// user id
+    pub id: String,
+    /// user name
+    pub username: String,
+    /// nickname in current guild, may be empty
+    #[serde(default)]
+    pub nickname: String,
+    /// four-digit identify number, used with username as `username#identify_num`
+    #[serde(default)]
+    pub identify_num: String,
+    /// if user is online
+    #[serde(default)]
+    pub online: bool,
+    /// if user is a bot
+    #[serde(default)]
+    pub bot: bool,
+    /// user status, 0 and 1 means normal, 10 means banned
+    #[serde(default)]
+    pub status: i64,
+    /// avatar url
+    #[serde(default)]
+    pub avatar: String,
+    /// vip avatar url, may be a gif
+    #[serde(default)]
+    pub vip_avatar: String,
+    /// if user has verified mobile phone
+    #[serde(default)]
+    pub mobile_verified: bool,
+    /// role ids in current guild
+    #[serde(default)]
+    pub roles: Vec<u64>,
+}
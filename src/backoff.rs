@@ -0,0 +1,192 @@
+//! retry delay policies
+
+use std::{fmt::Debug, sync::Arc, time::Duration};
+
+/// Decide how long to wait before next retry
+pub trait BackoffPolicy: Debug + Send + Sync {
+    /// delay before next retry, `last` is the delay before last retry, zero for the first retry
+    fn next_delay(&self, last: Duration) -> Duration;
+}
+
+/// Wait the same time before every retry
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Fixed(pub Duration);
+
+impl BackoffPolicy for Fixed {
+    fn next_delay(&self, _last: Duration) -> Duration {
+        self.0
+    }
+}
+
+/// Start from `initial` and double the delay every retry, until reaching `max`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Exponential {
+    /// delay before the first retry
+    pub initial: Duration,
+    /// max delay
+    pub max: Duration,
+}
+
+impl BackoffPolicy for Exponential {
+    fn next_delay(&self, last: Duration) -> Duration {
+        if last.is_zero() {
+            self.initial.min(self.max)
+        } else {
+            last.saturating_mul(2).max(self.initial).min(self.max)
+        }
+    }
+}
+
+/// Random delay between `base` and three times of last delay, capped by `max`.
+///
+/// See "Decorrelated Jitter" in
+/// <https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/>
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecorrelatedJitter {
+    /// min delay
+    pub base: Duration,
+    /// max delay
+    pub max: Duration,
+}
+
+impl BackoffPolicy for DecorrelatedJitter {
+    fn next_delay(&self, last: Duration) -> Duration {
+        let millis = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+        let low = millis(self.base);
+        let high = millis(last.saturating_mul(3).max(self.base));
+        Duration::from_millis(fastrand::u64(low..=high)).min(self.max)
+    }
+}
+
+/// A backoff policy with an optional attempts limit
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    policy: Arc<dyn BackoffPolicy>,
+    max_attempts: Option<usize>,
+}
+
+impl Backoff {
+    /// Retry forever using given policy
+    pub fn new<P: BackoffPolicy + 'static>(policy: P) -> Self {
+        Self {
+            policy: Arc::new(policy),
+            max_attempts: None,
+        }
+    }
+
+    /// Retry forever waiting the same time
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(Fixed(delay))
+    }
+
+    /// Retry forever with doubling delay
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self::new(Exponential { initial, max })
+    }
+
+    /// Retry forever with random delay
+    pub fn decorrelated_jitter(base: Duration, max: Duration) -> Self {
+        Self::new(DecorrelatedJitter { base, max })
+    }
+
+    /// Give up after tried `count` times in total, the first try included
+    pub fn max_attempts(mut self, count: usize) -> Self {
+        self.max_attempts.replace(count);
+        self
+    }
+
+    /// Delays before every retry, ends when attempts limit reached
+    pub fn delays(&self) -> Delays {
+        Delays {
+            backoff: self.clone(),
+            attempts: 1,
+            last: Duration::ZERO,
+        }
+    }
+}
+
+/// Iterator of retry delays, created by [Backoff::delays]
+#[derive(Debug, Clone)]
+pub struct Delays {
+    backoff: Backoff,
+    attempts: usize,
+    last: Duration,
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if matches!(self.backoff.max_attempts, Some(max) if self.attempts >= max) {
+            return None;
+        }
+
+        self.attempts += 1;
+        self.last = self.backoff.policy.next_delay(self.last);
+
+        Some(self.last)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exponential() {
+        let delays = Backoff::exponential(Duration::from_secs(2), Duration::from_secs(6))
+            .delays()
+            .take(4)
+            .map(|d| d.as_secs())
+            .collect::<Vec<_>>();
+
+        assert_eq!(delays, vec![2, 4, 6, 6]);
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let backoff = Backoff::fixed(Duration::from_secs(1)).max_attempts(3);
+        assert_eq!(backoff.delays().count(), 2);
+
+        let backoff = Backoff::fixed(Duration::from_secs(1)).max_attempts(0);
+        assert_eq!(backoff.delays().count(), 0);
+    }
+
+    #[test]
+    fn test_decorrelated_jitter() {
+        let base = Duration::from_millis(100);
+        let max = Duration::from_secs(1);
+        let mut last = Duration::ZERO;
+
+        for delay in Backoff::decorrelated_jitter(base, max).delays().take(100) {
+            assert!(delay >= base && delay <= max);
+            assert!(delay <= (last * 3).max(base));
+            last = delay;
+        }
+    }
+
+    #[test]
+    fn test_unbounded_max() {
+        let initial = Duration::from_secs(1);
+
+        let delays = Backoff::exponential(initial, Duration::MAX)
+            .delays()
+            .take(100)
+            .collect::<Vec<_>>();
+        assert!(delays.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(delays.last(), Some(&Duration::MAX));
+
+        for delay in Backoff::decorrelated_jitter(initial, Duration::MAX)
+            .delays()
+            .take(100)
+        {
+            assert!(delay >= initial);
+        }
+
+        let jitter = DecorrelatedJitter {
+            base: initial,
+            max: Duration::MAX,
+        };
+        assert!(jitter.next_delay(Duration::MAX) >= initial);
+    }
+}
@@ -1,11 +1,17 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use futures_util::StreamExt;
 use snafu::prelude::*;
 
 use crate::{
-    api::{self, types::GatewayURLInfo},
-    error, ws, Result,
+    api::{self, types::GatewayResumeArguments},
+    backoff::Backoff,
+    error,
+    filter::{Filter, Filtered},
+    handler::{Context, Dispatcher, Handler, Handlers},
+    source::{EventSource, WebsocketSource},
+    ws::client::{ClientConfig, EventStreamItem},
+    Result,
 };
 
 const RE_FETCH_GATEWAY_INTERVAL_MAX: u64 = 60;
@@ -13,89 +19,173 @@ const RE_FETCH_GATEWAY_INTERVAL_MAX: u64 = 60;
 /// Burz instance
 #[derive(Debug)]
 pub struct Bot {
-    #[allow(dead_code)]
-    api_client: api::Client,
+    api_client: Arc<api::Client>,
+    websocket: WebsocketSource,
+    source: Option<Box<dyn EventSource>>,
+    reopen_backoff: Backoff,
+    handlers: Handlers,
 }
 
 impl Bot {
-    /// Create new framework instance using bot token
+    /// Create new framework instance using bot token, events are received from websocket gateway
     pub fn new<S: AsRef<str> + ?Sized>(token: &S) -> Result<Self> {
-        let api_client = api::Client::new_from_bot_token(&token).context(error::CallAPIFailed)?;
+        let api_client =
+            Arc::new(api::Client::new_from_bot_token(&token).context(error::CallAPIFailed)?);
 
         log::info!("Crate api and websocket client success");
 
-        Ok(Self { api_client })
+        Ok(Self {
+            websocket: WebsocketSource::new(Arc::clone(&api_client)),
+            source: None,
+            reopen_backoff: Backoff::exponential(
+                Duration::from_secs(1),
+                Duration::from_secs(RE_FETCH_GATEWAY_INTERVAL_MAX),
+            ),
+            api_client,
+            handlers: Handlers::default(),
+        })
     }
 
-    // async fn fetch_new_gateway(&self) -> Result<GatewayURLInfo> {
-    //     let gateway_str = self
-    //         .api_client
-    //         .gateway_url()
-    //         .await
-    //         .context(error::CallAPIFailed)?;
+    /// Receive events from given source instead of websocket gateway
+    pub fn with_source<S: EventSource + 'static>(mut self, source: S) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Set timing and capacity settings of the websocket client.
+    ///
+    /// Ignored when events are received from a custom source.
+    pub fn with_client_config(mut self, config: ClientConfig) -> Self {
+        self.websocket = self.websocket.with_config(config);
+        self
+    }
+
+    /// Set retry policy of opening the event source, [run](Self::run) returns the last error when
+    /// attempts limit reached.
+    ///
+    /// Default policy starts from 1 second and doubles the delay up to 60 seconds, never gives up.
+    pub fn with_reopen_backoff(mut self, backoff: Backoff) -> Self {
+        self.reopen_backoff = backoff;
+        self
+    }
+
+    /// Register an event handler, every received event will be dispatched to all handlers
+    /// in registration order.
+    pub fn with_handler<H: Handler + 'static>(mut self, handler: H) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    /// Register an event handler which only receives events passing the filter
+    pub fn with_filtered_handler<H: Handler + 'static>(self, filter: Filter, handler: H) -> Self {
+        self.with_handler(Filtered::new(filter, handler))
+    }
 
-    //     gateway_str
-    //         .parse()
-    //         .with_context(|_| error::InvalidGatewayURL { url: &gateway_url })
-    // }
+    /// Get the api client used by this bot
+    pub fn api(&self) -> &api::Client {
+        &self.api_client
+    }
 
-    async fn fetch_new_gateway(&self) -> Result<GatewayURLInfo> {
-        Ok("ws://127.0.0.1:7777/gateway?token=x&compress=0"
-            .parse()
-            .unwrap())
+    /// Always connect to given gateway url instead of fetching one from `/gateway/index`.
+    ///
+    /// This is mainly used for testing with a local fake gateway.
+    /// Ignored when events are received from a custom source.
+    pub fn with_gateway_url<S: AsRef<str> + ?Sized>(mut self, url: &S) -> Result<Self> {
+        self.websocket = self.websocket.with_gateway_url(url)?;
+        Ok(self)
     }
 
-    /// Run
+    /// Run until the event source is exhausted or failed.
+    ///
+    /// Handlers receive events and notices one by one in the order of the event stream,
+    /// a slow handler delays all later items and eventually pauses receiving from the source.
+    /// A panicking handler only loses the item it was handling.
     pub async fn run(self) -> Result<()> {
-        let mut resume = None;
-        let mut refetch_delay = 1;
+        let source = match self.source {
+            Some(source) => source,
+            None => Box::new(self.websocket),
+        };
+        let ctx = Context::new(Arc::clone(&self.api_client));
+        let dispatcher = self.handlers.start(ctx);
 
-        loop {
-            log::info!("Getting gateway url ...");
+        let result = Self::receive(source, &self.reopen_backoff, &dispatcher).await;
 
-            let gateway_info = self.fetch_new_gateway().await?;
+        log::debug!("Waiting handlers to finish dispatched items");
+        dispatcher.finish().await;
 
-            log::debug!("Got gateway url: {}", gateway_info.url());
+        result
+    }
 
-            let ws_client = if let Some(r) = resume.take() {
-                log::debug!("Resume conversion using argument: {:?}", r);
-                ws::Client::resume(r)
-            } else {
-                ws::Client::new()
-            };
+    async fn receive(
+        mut source: Box<dyn EventSource>,
+        reopen_backoff: &Backoff,
+        dispatcher: &Dispatcher,
+    ) -> Result<()> {
+        let mut resume: Option<GatewayResumeArguments> = None;
+        let mut reopen_delays = reopen_backoff.delays();
 
-            let mut stream = match ws_client.run(gateway_info).await {
+        loop {
+            log::info!("Opening event source ...");
+
+            let mut stream = match source.open(resume.clone()).await {
                 Ok(stream) => stream,
-                Err(err) => {
-                    log::warn!("Can't establish event stream with fetched url: {}", err);
-                    log::warn!(
-                        "Retry fetch new gateway url after {} seconds ...",
-                        refetch_delay
-                    );
+                Err(err) if err.is_retryable() => {
+                    log::warn!("Can't establish event stream: {}", err);
+
+                    if !err.can_resume() && resume.take().is_some() {
+                        log::info!("Last session can't be resumed, start a new session next time");
+                    }
 
-                    tokio::time::sleep(Duration::from_secs(refetch_delay)).await;
-                    refetch_delay *= 2;
-                    refetch_delay = refetch_delay.clamp(1, RE_FETCH_GATEWAY_INTERVAL_MAX);
+                    let delay = match reopen_delays.next() {
+                        Some(delay) => delay,
+                        None => {
+                            log::error!("Reached max attempts of opening event source, stop");
+                            return Err(err);
+                        }
+                    };
+
+                    log::warn!("Retry open event source after {:?} ...", delay);
+
+                    tokio::time::sleep(delay).await;
 
                     continue;
                 }
+                Err(err) => return Err(err),
             };
 
-            refetch_delay = 1;
+            reopen_delays = reopen_backoff.delays();
 
             log::info!("Event stream established, start receiving events");
 
             loop {
-                let item = stream.next().await.unwrap();
-                match item {
-                    Ok(event) => {
-                        log::info!("Received event: {:?}", event)
+                match stream.next().await {
+                    None => {
+                        log::info!("Event source exhausted, stop");
+                        return Ok(());
+                    }
+                    Some(Ok(item)) => {
+                        match item {
+                            EventStreamItem::Event(ref event) => {
+                                log::debug!("Received event: {:?}", event)
+                            }
+                            EventStreamItem::Notice(ref notice) => {
+                                log::warn!("Event stream notice: {}", notice)
+                            }
+                        }
+                        if !dispatcher.dispatch(item).await {
+                            return error::DispatchStopped.fail();
+                        }
                     }
-                    Err(err) => {
+                    Some(Err(err)) => {
                         log::warn!("EventStream broken, reason: {}", err.source);
-                        log::debug!("Resume argument: {:?}", err.resume);
 
-                        resume.replace(err.resume);
+                        if err.can_resume() {
+                            log::debug!("Resume argument: {:?}", err.resume);
+                            resume.replace(err.resume);
+                        } else {
+                            log::info!("Last session can't be resumed, start a new session");
+                            resume = None;
+                        }
 
                         log::info!("Bot Restart");
 
@@ -106,3 +196,201 @@ impl Bot {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use hyper::StatusCode;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        api::fake,
+        source::ChannelSource,
+        ws::{client::fake::FakeGateway, Event},
+        Error,
+    };
+
+    #[tokio::test]
+    async fn test_bot_run_with_channel_source() {
+        let (source, sender) = ChannelSource::new(4);
+        let (handled_tx, mut handled_rx) = mpsc::unbounded_channel();
+
+        let bot =
+            Bot::new("token")
+                .unwrap()
+                .with_source(source)
+                .with_handler(move |_ctx, event| {
+                    let handled_tx = handled_tx.clone();
+                    async move {
+                        handled_tx.send(event).unwrap();
+                    }
+                });
+
+        let event = Event::Unknown(serde_json::json!({ "hello": "world" }));
+        sender
+            .send(Ok(EventStreamItem::Event(event.clone())))
+            .await
+            .unwrap();
+        drop(sender);
+
+        bot.run().await.unwrap();
+
+        assert_eq!(handled_rx.recv().await, Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_bot_reopen_max_attempts() {
+        // a closed local port, connecting to it always fails
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let config = ClientConfig {
+            connect_backoff: Backoff::fixed(Duration::ZERO).max_attempts(1),
+            ..ClientConfig::default()
+        };
+
+        let bot = Bot::new("token")
+            .unwrap()
+            .with_gateway_url(&format!("ws://{}/gateway?token=test", addr))
+            .unwrap()
+            .with_client_config(config)
+            .with_reopen_backoff(Backoff::fixed(Duration::from_millis(1)).max_attempts(3));
+
+        let result = bot.run().await;
+
+        assert!(matches!(
+            result,
+            Err(Error::RunWebsocketClientFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bot_reopen_after_gateway_closed() {
+        let mut gateway = FakeGateway::start().await;
+
+        let bot = Bot::new("token")
+            .unwrap()
+            .with_gateway_url(gateway.url())
+            .unwrap();
+        let bot = tokio::spawn(bot.run());
+
+        let mut conn = gateway.accept().await;
+        conn.hello("test-session").await;
+        conn.event(1).await;
+        conn.close().await;
+
+        // bot should not stop, but reopen and resume the session
+        let conn = tokio::time::timeout(Duration::from_secs(5), gateway.accept())
+            .await
+            .expect("bot did not reconnect");
+        assert_eq!(conn.query["resume"], "1");
+        assert_eq!(conn.query["session_id"], "test-session");
+        assert_eq!(conn.query["sn"], "1");
+        assert!(!bot.is_finished());
+
+        bot.abort();
+    }
+
+    /// Let the bot run a session which breaks after event 1, then answer the resume
+    /// hello with `code`, returns query of the connection opened after that
+    async fn reopen_after_hello_code(code: i64) -> std::collections::HashMap<String, String> {
+        let mut gateway = FakeGateway::start().await;
+
+        let bot = Bot::new("token")
+            .unwrap()
+            .with_gateway_url(gateway.url())
+            .unwrap()
+            .with_reopen_backoff(Backoff::fixed(Duration::from_millis(1)));
+        let bot = tokio::spawn(bot.run());
+
+        let mut conn = gateway.accept().await;
+        conn.hello("test-session").await;
+        conn.event(1).await;
+        conn.close().await;
+
+        let mut conn = gateway.accept().await;
+        assert_eq!(conn.query["session_id"], "test-session");
+        conn.send(serde_json::json!({ "s": 1, "d": { "code": code } }))
+            .await;
+
+        let conn = gateway.accept().await;
+        bot.abort();
+        conn.query
+    }
+
+    #[tokio::test]
+    async fn test_bot_keep_resuming_after_token_error() {
+        // token expired, 40103
+        let query = reopen_after_hello_code(40103).await;
+        assert_eq!(query["resume"], "1");
+        assert_eq!(query["session_id"], "test-session");
+        assert_eq!(query["sn"], "1");
+    }
+
+    #[tokio::test]
+    async fn test_bot_start_new_session_after_missing_params() {
+        // missing params, 40100
+        let query = reopen_after_hello_code(40100).await;
+        assert!(!query.contains_key("resume"));
+        assert!(!query.contains_key("session_id"));
+        assert!(!query.contains_key("sn"));
+    }
+
+    /// A bot fetching gateway url from a fake api server, which fails the first `failures` calls
+    fn bot_with_failing_gateway_index(
+        gateway_url: String,
+        failures: usize,
+    ) -> (Bot, mpsc::UnboundedReceiver<fake::Received>) {
+        let calls = AtomicUsize::new(0);
+        let (client, received) = fake::serve(move |_path| {
+            if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                (StatusCode::INTERNAL_SERVER_ERROR, serde_json::Value::Null)
+            } else {
+                (StatusCode::OK, serde_json::json!({ "url": gateway_url }))
+            }
+        });
+
+        let bot = Bot::new("token")
+            .unwrap()
+            .with_source(WebsocketSource::new(Arc::new(client)));
+
+        (bot, received)
+    }
+
+    #[tokio::test]
+    async fn test_bot_retry_fetching_gateway() {
+        let mut gateway = FakeGateway::start().await;
+
+        let (bot, mut received) = bot_with_failing_gateway_index(gateway.url().to_string(), 1);
+        let bot = bot.with_reopen_backoff(Backoff::fixed(Duration::from_millis(1)).max_attempts(3));
+        let bot = tokio::spawn(bot.run());
+
+        tokio::time::timeout(Duration::from_secs(5), gateway.accept())
+            .await
+            .expect("bot did not retry fetching gateway url");
+
+        for _ in 0..2 {
+            assert_eq!(received.recv().await.unwrap().path, "/gateway/index");
+        }
+        assert!(!bot.is_finished());
+
+        bot.abort();
+    }
+
+    #[tokio::test]
+    async fn test_bot_fetch_gateway_max_attempts() {
+        let (bot, mut received) = bot_with_failing_gateway_index(String::new(), usize::MAX);
+        let bot = bot.with_reopen_backoff(Backoff::fixed(Duration::from_millis(1)).max_attempts(3));
+
+        let result = bot.run().await;
+
+        assert!(matches!(result, Err(Error::CallAPIFailed { .. })));
+        for _ in 0..3 {
+            assert_eq!(received.recv().await.unwrap().path, "/gateway/index");
+        }
+        assert!(received.try_recv().is_err());
+    }
+}
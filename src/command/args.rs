@@ -0,0 +1,385 @@
+use std::{collections::HashMap, time::Duration};
+
+use snafu::prelude::*;
+
+/// Error when parse command arguments
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[snafu(visibility(pub(crate)), module(error), context(suffix(false)))]
+pub enum ParseArgError {
+    /// a quoted string has no close quote
+    #[snafu(display("missing closing quote in arguments"))]
+    UnclosedQuote,
+
+    /// required argument not given
+    #[snafu(display("missing argument `{name}`"))]
+    MissingArgument {
+        /// argument name
+        name: String,
+    },
+
+    /// more arguments than needed
+    #[snafu(display("unexpected extra argument `{value}`"))]
+    TooManyArguments {
+        /// the first extra argument
+        value: String,
+    },
+
+    /// argument can't be parsed as target kind
+    #[snafu(display("argument `{name}` should be {}, but got `{value}`", kind.describe()))]
+    InvalidArgument {
+        /// argument name
+        name: String,
+        /// expected kind
+        kind: ArgKind,
+        /// received value
+        value: String,
+    },
+}
+
+/// Kind of a command argument
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArgKind {
+    /// a signed integer
+    Integer,
+    /// a word or a double quoted string
+    String,
+    /// a user mention like `(met)123(met)`, or a raw user id
+    User,
+    /// a channel mention like `(chn)123(chn)`, or a raw channel id
+    Channel,
+    /// a role mention like `(rol)123(rol)`, or a raw role id
+    Role,
+    /// a duration like `30s`, `5m`, `1h30m`, `2d`
+    Duration,
+    /// all remaining text, must be the last argument
+    Rest,
+}
+
+impl ArgKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Integer => "an integer",
+            Self::String => "a string",
+            Self::User => "a user mention",
+            Self::Channel => "a channel mention",
+            Self::Role => "a role mention",
+            Self::Duration => "a duration like 30s, 5m or 1h30m",
+            Self::Rest => "some text",
+        }
+    }
+
+    fn parse(&self, name: &str, token: &str) -> Result<Arg, ParseArgError> {
+        let arg = match self {
+            Self::Integer => token.parse().ok().map(Arg::Integer),
+            Self::String | Self::Rest => Some(Arg::String(token.to_string())),
+            Self::User => parse_mention(token, "met").map(|id| Arg::User(id.to_string())),
+            Self::Channel => parse_mention(token, "chn").map(|id| Arg::Channel(id.to_string())),
+            Self::Role => parse_mention(token, "rol")
+                .and_then(|id| id.parse().ok())
+                .map(Arg::Role),
+            Self::Duration => parse_duration(token).map(Arg::Duration),
+        };
+
+        arg.with_context(|| error::InvalidArgument {
+            name,
+            kind: *self,
+            value: token,
+        })
+    }
+}
+
+/// Parsed command argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Arg {
+    /// integer argument
+    Integer(i64),
+    /// string argument
+    String(String),
+    /// mentioned user id
+    User(String),
+    /// mentioned channel id
+    Channel(String),
+    /// mentioned role id
+    Role(u64),
+    /// duration argument
+    Duration(Duration),
+}
+
+/// Argument definition of a command
+#[derive(Debug, Clone)]
+pub(crate) struct ArgSpec {
+    pub name: String,
+    pub kind: ArgKind,
+    pub optional: bool,
+}
+
+impl ArgSpec {
+    pub fn usage(&self) -> String {
+        if self.optional {
+            format!("[{}]", self.name)
+        } else {
+            format!("<{}>", self.name)
+        }
+    }
+}
+
+/// Parsed arguments of a command invocation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    values: HashMap<String, Arg>,
+}
+
+impl Args {
+    /// get argument by name
+    pub fn get(&self, name: &str) -> Option<&Arg> {
+        self.values.get(name)
+    }
+
+    /// get integer argument by name
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            Arg::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// get string argument by name
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Arg::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// get mentioned user id by name
+    pub fn user(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Arg::User(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// get mentioned channel id by name
+    pub fn channel(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Arg::Channel(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// get mentioned role id by name
+    pub fn role(&self, name: &str) -> Option<u64> {
+        match self.get(name)? {
+            Arg::Role(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// get duration argument by name
+    pub fn duration(&self, name: &str) -> Option<Duration> {
+        match self.get(name)? {
+            Arg::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse(specs: &[ArgSpec], input: &str) -> Result<Self, ParseArgError> {
+        let mut values = HashMap::new();
+        let mut rest = input;
+
+        for spec in specs {
+            rest = rest.trim_start();
+
+            if spec.kind == ArgKind::Rest {
+                if !rest.is_empty() {
+                    values.insert(spec.name.clone(), spec.kind.parse(&spec.name, rest)?);
+                } else {
+                    ensure!(spec.optional, error::MissingArgument { name: &spec.name });
+                }
+                rest = "";
+                continue;
+            }
+
+            match next_token(rest)? {
+                Some((token, remain)) => {
+                    values.insert(spec.name.clone(), spec.kind.parse(&spec.name, &token)?);
+                    rest = remain;
+                }
+                None => {
+                    ensure!(spec.optional, error::MissingArgument { name: &spec.name });
+                }
+            }
+        }
+
+        if let Some((token, _)) = next_token(rest)? {
+            return error::TooManyArguments { value: token }.fail();
+        }
+
+        Ok(Self { values })
+    }
+}
+
+/// Split next token from input, returns the token and the remaining input.
+///
+/// A token is a run of non-whitespace characters, or a double quoted string
+/// in which `\"` and `\\` are escaped.
+pub(crate) fn next_token(input: &str) -> Result<Option<(String, &str)>, ParseArgError> {
+    let input = input.trim_start();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(quoted) = input.strip_prefix('"') {
+        let mut token = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok(Some((token, &quoted[i + 1..]))),
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => token.push(escaped),
+                    None => break,
+                },
+                _ => token.push(c),
+            }
+        }
+        return error::UnclosedQuote.fail();
+    }
+
+    let end = input.find(char::is_whitespace).unwrap_or(input.len());
+    Ok(Some((input[..end].to_string(), &input[end..])))
+}
+
+fn parse_mention<'a>(token: &'a str, tag: &str) -> Option<&'a str> {
+    let id = token
+        .strip_prefix(&format!("({})", tag))
+        .and_then(|s| s.strip_suffix(&format!("({})", tag)))
+        .unwrap_or(token);
+
+    if !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+fn parse_duration(token: &str) -> Option<Duration> {
+    let mut total = 0u64;
+    let mut number: Option<u64> = None;
+
+    for c in token.chars() {
+        if let Some(d) = c.to_digit(10) {
+            number = Some(number.unwrap_or(0).checked_mul(10)?.checked_add(d.into())?);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+
+        total = total.checked_add(number.take()?.checked_mul(unit)?)?;
+    }
+
+    // a bare number means seconds
+    if let Some(n) = number {
+        total = total.checked_add(n)?;
+    }
+
+    if token.is_empty() {
+        None
+    } else {
+        Some(Duration::from_secs(total))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(name: &str, kind: ArgKind, optional: bool) -> ArgSpec {
+        ArgSpec {
+            name: name.to_string(),
+            kind,
+            optional,
+        }
+    }
+
+    #[test]
+    fn test_next_token_quoted() {
+        let (token, rest) = next_token(r#"  "hello \"world\"" next"#).unwrap().unwrap();
+        assert_eq!(token, r#"hello "world""#);
+        assert_eq!(rest, " next");
+
+        assert_eq!(
+            next_token(r#""unclosed"#),
+            Err(ParseArgError::UnclosedQuote)
+        );
+        assert_eq!(next_token("   "), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172800)));
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let specs = [
+            spec("user", ArgKind::User, false),
+            spec("time", ArgKind::Duration, false),
+            spec("role", ArgKind::Role, false),
+            spec("count", ArgKind::Integer, true),
+            spec("reason", ArgKind::Rest, true),
+        ];
+
+        let args =
+            Args::parse(&specs, "(met)123(met) 10m (rol)456(rol) -3 spam and flood").unwrap();
+        assert_eq!(args.user("user"), Some("123"));
+        assert_eq!(args.duration("time"), Some(Duration::from_secs(600)));
+        assert_eq!(args.role("role"), Some(456));
+        assert_eq!(args.integer("count"), Some(-3));
+        assert_eq!(args.string("reason"), Some("spam and flood"));
+
+        let args = Args::parse(&specs, "123 10m 456").unwrap();
+        assert_eq!(args.integer("count"), None);
+        assert_eq!(args.string("reason"), None);
+    }
+
+    #[test]
+    fn test_parse_args_error() {
+        let specs = [
+            spec("count", ArgKind::Integer, false),
+            spec("channel", ArgKind::Channel, true),
+        ];
+
+        assert_eq!(
+            Args::parse(&specs, ""),
+            Err(ParseArgError::MissingArgument {
+                name: "count".to_string()
+            })
+        );
+        assert!(matches!(
+            Args::parse(&specs, "abc"),
+            Err(ParseArgError::InvalidArgument {
+                kind: ArgKind::Integer,
+                ..
+            })
+        ));
+        assert_eq!(
+            Args::parse(&specs, "1 (chn)2(chn) 3"),
+            Err(ParseArgError::TooManyArguments {
+                value: "3".to_string()
+            })
+        );
+    }
+}
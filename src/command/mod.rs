@@ -0,0 +1,447 @@
+//! command framework, parse message content as commands and route them to handlers
+
+mod args;
+
+pub use args::{Arg, ArgKind, Args, ParseArgError};
+
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use snafu::prelude::*;
+
+use crate::{
+    handler::{Context, Handler},
+    ws::{
+        event::{MessageEvent, MessageType},
+        Event,
+    },
+};
+use args::ArgSpec;
+
+/// Prefix which marks a message as a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prefix {
+    /// a text prefix, like `/` or `!`
+    Text(String),
+    /// mention of the bot itself, like `(met)bot_id(met)`
+    Mention,
+}
+
+impl From<&str> for Prefix {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<String> for Prefix {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+/// A parsed command invocation
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    /// command name
+    pub name: String,
+    /// the name or alias used by the invoker
+    pub invoked_as: String,
+    /// parsed arguments
+    pub args: Args,
+    /// the message contains this command
+    pub message: MessageEvent,
+}
+
+/// Command handler
+///
+/// Any `Fn(Context, Invocation) -> impl Future<Output = ()>` closure is a command handler.
+pub trait CommandHandler: Send + Sync {
+    /// handle a command invocation
+    fn call(&self, ctx: Context, invocation: Invocation) -> BoxFuture<'_, ()>;
+}
+
+impl<F, Fut> CommandHandler for F
+where
+    F: Fn(Context, Invocation) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn call(&self, ctx: Context, invocation: Invocation) -> BoxFuture<'_, ()> {
+        Box::pin(self(ctx, invocation))
+    }
+}
+
+/// A named command
+pub struct Command {
+    name: String,
+    aliases: Vec<String>,
+    description: String,
+    args: Vec<ArgSpec>,
+    handler: Box<dyn CommandHandler>,
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("aliases", &self.aliases)
+            .field("description", &self.description)
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+impl Command {
+    /// Create a new command with name and handler
+    pub fn new<S: Into<String>, H: CommandHandler + 'static>(name: S, handler: H) -> Self {
+        Self {
+            name: name.into(),
+            aliases: Vec::new(),
+            description: String::new(),
+            args: Vec::new(),
+            handler: Box::new(handler),
+        }
+    }
+
+    /// Add an alias name
+    pub fn alias<S: Into<String>>(mut self, alias: S) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Set description
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Add a required argument
+    ///
+    /// # Panics
+    ///
+    /// Panics if an optional or [Rest](ArgKind::Rest) argument is added before it.
+    pub fn arg<S: Into<String>>(self, name: S, kind: ArgKind) -> Self {
+        self.push_arg(name.into(), kind, false)
+    }
+
+    /// Add an optional argument, all arguments after it must be optional too
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [Rest](ArgKind::Rest) argument is added before it.
+    pub fn optional_arg<S: Into<String>>(self, name: S, kind: ArgKind) -> Self {
+        self.push_arg(name.into(), kind, true)
+    }
+
+    fn push_arg(mut self, name: String, kind: ArgKind, optional: bool) -> Self {
+        if let Some(last) = self.args.last() {
+            assert!(
+                last.kind != ArgKind::Rest,
+                "command {}: argument {} follows rest argument {}",
+                self.name,
+                name,
+                last.name
+            );
+            assert!(
+                optional || !last.optional,
+                "command {}: required argument {} follows optional argument {}",
+                self.name,
+                name,
+                last.name
+            );
+        }
+
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            optional,
+        });
+        self
+    }
+
+    /// Command name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Usage string like `name <arg1> [arg2]`
+    pub fn usage(&self) -> String {
+        std::iter::once(self.name.clone())
+            .chain(self.args.iter().map(ArgSpec::usage))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Error when handle a command message
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), module(error), context(suffix(false)))]
+pub enum CommandError {
+    /// parse command arguments failed
+    #[snafu(display("{source}, usage: {usage}"))]
+    ParseArgsFailed {
+        /// command name
+        command: String,
+        /// command usage
+        usage: String,
+        /// source error
+        source: ParseArgError,
+    },
+}
+
+type ErrorHandler =
+    Arc<dyn Fn(Context, MessageEvent, CommandError) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Command router, register it to [Bot](crate::Bot) as a handler
+pub struct Commands {
+    prefixes: Vec<Prefix>,
+    commands: HashMap<String, Arc<Command>>,
+    ignore_bots: bool,
+    error_handler: Option<ErrorHandler>,
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("prefixes", &self.prefixes)
+            .field("commands", &self.commands)
+            .field("ignore_bots", &self.ignore_bots)
+            .finish()
+    }
+}
+
+impl Default for Commands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Commands {
+    /// Create a new command router, using `/` as prefix if no other prefix is added
+    pub fn new() -> Self {
+        Self {
+            prefixes: Vec::new(),
+            commands: HashMap::new(),
+            ignore_bots: true,
+            error_handler: None,
+        }
+    }
+
+    /// Add a command prefix
+    pub fn prefix<P: Into<Prefix>>(mut self, prefix: P) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Set if messages sent by bots are ignored, default is true
+    pub fn ignore_bots(mut self, ignore: bool) -> Self {
+        self.ignore_bots = ignore;
+        self
+    }
+
+    /// Register a command
+    pub fn command(mut self, command: Command) -> Self {
+        let command = Arc::new(command);
+        for name in std::iter::once(&command.name).chain(command.aliases.iter()) {
+            if self
+                .commands
+                .insert(name.clone(), Arc::clone(&command))
+                .is_some()
+            {
+                log::warn!("Command name {} registered twice, override it", name);
+            }
+        }
+        self
+    }
+
+    /// Set handler for command errors, like invalid arguments.
+    ///
+    /// By default the error is replied to the message which contains the command.
+    pub fn on_error<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Context, MessageEvent, CommandError) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.error_handler
+            .replace(Arc::new(move |ctx, message, err| {
+                Box::pin(f(ctx, message, err))
+            }));
+        self
+    }
+
+    async fn strip_prefix<'a>(&self, ctx: &Context, content: &'a str) -> Option<&'a str> {
+        if self.prefixes.is_empty() {
+            return content.strip_prefix('/');
+        }
+
+        for prefix in &self.prefixes {
+            let stripped = match prefix {
+                Prefix::Text(p) => content.strip_prefix(p.as_str()),
+                Prefix::Mention => match ctx.me().await {
+                    Ok(me) => content
+                        .strip_prefix(&format!("(met){}(met)", me.id))
+                        .map(str::trim_start),
+                    Err(err) => {
+                        log::warn!("Get bot user info failed, skip mention prefix: {}", err);
+                        None
+                    }
+                },
+            };
+
+            if stripped.is_some() {
+                return stripped;
+            }
+        }
+
+        None
+    }
+
+    async fn report_error(&self, ctx: Context, message: MessageEvent, err: CommandError) {
+        if let Some(ref handler) = self.error_handler {
+            return handler(ctx, message, err).await;
+        }
+
+        log::debug!("Command in message {} failed: {}", message.msg_id, err);
+
+        if let Err(err) = ctx.reply(&message, err.to_string()).await {
+            log::warn!("Reply command error failed: {}", err);
+        }
+    }
+
+    async fn on_message(&self, ctx: Context, message: MessageEvent) {
+        if !matches!(
+            message.message_type,
+            MessageType::Text | MessageType::KMarkdown
+        ) || (self.ignore_bots && message.is_from_bot())
+        {
+            return;
+        }
+
+        let content = message.content.trim();
+        let input = match self.strip_prefix(&ctx, content).await {
+            Some(input) => input,
+            None => return,
+        };
+
+        let (invoked_as, input) = match args::next_token(input) {
+            Ok(Some((name, remain))) => (name, remain),
+            _ => return,
+        };
+
+        let command = match self.commands.get(&invoked_as) {
+            Some(command) => Arc::clone(command),
+            None => {
+                log::trace!("Unknown command {}, ignore", invoked_as);
+                return;
+            }
+        };
+
+        let args =
+            match Args::parse(&command.args, input).with_context(|_| error::ParseArgsFailed {
+                command: &command.name,
+                usage: command.usage(),
+            }) {
+                Ok(args) => args,
+                Err(err) => {
+                    self.report_error(ctx, message, err).await;
+                    return;
+                }
+            };
+
+        log::debug!("Run command {} in message {}", command.name, message.msg_id);
+
+        let invocation = Invocation {
+            name: command.name.clone(),
+            invoked_as,
+            args,
+            message,
+        };
+
+        command.handler.call(ctx, invocation).await
+    }
+}
+
+impl Handler for Commands {
+    fn handle(&self, ctx: Context, event: Event) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            if let Event::Message(message) = event {
+                self.on_message(ctx, *message).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::fake;
+
+    #[tokio::test]
+    async fn test_reply_parse_error_by_default() {
+        let (client, mut received) = fake::serve(|_| {
+            (
+                StatusCode::OK,
+                json!({ "msg_id": "reply", "msg_timestamp": 1607680283000u64 }),
+            )
+        });
+        let ctx = Context::new(Arc::new(client));
+
+        let commands = Commands::new()
+            .command(Command::new("add", |_, _| async {}).arg("a", ArgKind::Integer));
+
+        let message: MessageEvent = serde_json::from_value(json!({
+            "channel_type": "GROUP",
+            "type": 1,
+            "target_id": "channel",
+            "author_id": "user",
+            "content": "/add one",
+            "msg_id": "command",
+            "msg_timestamp": 1607680283000u64,
+            "extra": {
+                "type": 1,
+                "guild_id": "guild",
+                "author": { "id": "user", "username": "user" },
+            },
+        }))
+        .unwrap();
+
+        commands.on_message(ctx, message).await;
+
+        let request = received.recv().await.unwrap();
+        assert_eq!(request.path, "/message/create");
+        assert_eq!(request.body["target_id"], "channel");
+        assert_eq!(request.body["quote"], "command");
+        assert!(request.body["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("usage: add <a>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "required argument b follows optional argument a")]
+    fn test_required_arg_after_optional() {
+        Command::new("add", |_, _| async {})
+            .optional_arg("a", ArgKind::Integer)
+            .arg("b", ArgKind::Integer);
+    }
+
+    #[test]
+    #[should_panic(expected = "argument b follows rest argument a")]
+    fn test_arg_after_rest() {
+        Command::new("say", |_, _| async {})
+            .optional_arg("a", ArgKind::Rest)
+            .optional_arg("b", ArgKind::String);
+    }
+
+    #[test]
+    fn test_optional_args_after_required() {
+        let command = Command::new("say", |_, _| async {})
+            .arg("who", ArgKind::User)
+            .optional_arg("times", ArgKind::Integer)
+            .optional_arg("text", ArgKind::Rest);
+
+        assert_eq!(command.usage(), "say <who> [times] [text]");
+    }
+}
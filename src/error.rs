@@ -3,6 +3,7 @@
 use snafu::prelude::*;
 
 use super::api::Error as APIError;
+use super::webhook::WebhookError;
 use super::ws::client::RunError;
 
 /// framework result type
@@ -32,6 +33,46 @@ pub enum Error {
     #[snafu(display("run inner websocket client failed: {source}"))]
     RunWebsocketClientFailed {
         /// source error
-        source: RunError,
+        #[snafu(source(from(RunError, Box::new)))]
+        source: Box<RunError>,
     },
+
+    /// Start webhook server failed
+    #[snafu(display("start webhook server failed: {source}"))]
+    StartWebhookServerFailed {
+        /// source error
+        source: WebhookError,
+    },
+
+    /// Background task dispatching events to handlers stopped unexpectedly
+    #[snafu(display("event dispatch task stopped"))]
+    DispatchStopped,
+
+    /// Read event replay file failed
+    #[snafu(display("read replay file {} failed: {source}", path.display()))]
+    ReadReplayFileFailed {
+        /// file path
+        path: std::path::PathBuf,
+        /// source io error
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    /// if opening event source again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RunWebsocketClientFailed { .. } => true,
+            Self::CallAPIFailed { source } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// if last session can still be resumed after this error
+    pub fn can_resume(&self) -> bool {
+        match self {
+            Self::RunWebsocketClientFailed { source } => source.can_resume(),
+            _ => true,
+        }
+    }
 }
@@ -0,0 +1,297 @@
+//! composable event filters
+
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context as TaskContext, Poll},
+};
+
+use futures_util::{future::BoxFuture, Stream};
+use regex::Regex;
+
+use crate::{
+    handler::{Context, Handler},
+    ws::{
+        client::{EventStreamItem, Notice},
+        event::{ChannelType, MessageType},
+        Event,
+    },
+};
+
+/// Event filter, decide which events should be handled
+#[derive(Clone)]
+pub enum Filter {
+    /// events happened in given guild
+    Guild(String),
+    /// messages sent in given channel
+    Channel(String),
+    /// messages sent by given user
+    Author(String),
+    /// messages of given type
+    MessageType(MessageType),
+    /// events not from bots, system events are always passed
+    NotBot,
+    /// messages mention given user
+    Mentions(String),
+    /// messages mention the bot itself
+    MentionsMe,
+    /// messages whose content matches the regex
+    Content(Regex),
+    /// custom filter function
+    Custom(Arc<dyn Fn(&Event) -> bool + Send + Sync>),
+    /// both filters pass
+    And(Box<Filter>, Box<Filter>),
+    /// any filter passes
+    Or(Box<Filter>, Box<Filter>),
+    /// the filter not passes
+    Not(Box<Filter>),
+}
+
+impl Debug for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Guild(id) => f.debug_tuple("Guild").field(id).finish(),
+            Self::Channel(id) => f.debug_tuple("Channel").field(id).finish(),
+            Self::Author(id) => f.debug_tuple("Author").field(id).finish(),
+            Self::MessageType(t) => f.debug_tuple("MessageType").field(t).finish(),
+            Self::NotBot => f.write_str("NotBot"),
+            Self::Mentions(id) => f.debug_tuple("Mentions").field(id).finish(),
+            Self::MentionsMe => f.write_str("MentionsMe"),
+            Self::Content(re) => f.debug_tuple("Content").field(re).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+            Self::And(a, b) => f.debug_tuple("And").field(a).field(b).finish(),
+            Self::Or(a, b) => f.debug_tuple("Or").field(a).field(b).finish(),
+            Self::Not(a) => f.debug_tuple("Not").field(a).finish(),
+        }
+    }
+}
+
+impl Filter {
+    /// events happened in given guild
+    pub fn guild<S: Into<String>>(id: S) -> Self {
+        Self::Guild(id.into())
+    }
+
+    /// messages sent in given channel
+    pub fn channel<S: Into<String>>(id: S) -> Self {
+        Self::Channel(id.into())
+    }
+
+    /// messages sent by given user
+    pub fn author<S: Into<String>>(id: S) -> Self {
+        Self::Author(id.into())
+    }
+
+    /// messages mention given user
+    pub fn mentions<S: Into<String>>(id: S) -> Self {
+        Self::Mentions(id.into())
+    }
+
+    /// messages whose content matches the regex
+    pub fn content(re: Regex) -> Self {
+        Self::Content(re)
+    }
+
+    /// custom filter function
+    pub fn custom<F: Fn(&Event) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// pass only when both filters pass
+    pub fn and(self, other: Filter) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// pass when any filter passes
+    pub fn or(self, other: Filter) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// check if this filter needs to know bot's own user id
+    pub fn needs_me(&self) -> bool {
+        match self {
+            Self::MentionsMe => true,
+            Self::And(a, b) | Self::Or(a, b) => a.needs_me() || b.needs_me(),
+            Self::Not(a) => a.needs_me(),
+            _ => false,
+        }
+    }
+
+    /// check if the event passes this filter.
+    ///
+    /// `me` is bot's own user id, [`Filter::MentionsMe`] never passes if it's `None`.
+    pub fn matches(&self, event: &Event, me: Option<&str>) -> bool {
+        let message = event.as_message();
+
+        match self {
+            Self::Guild(id) => match event {
+                Event::Message(m) => m.guild_id() == Some(id.as_str()),
+                Event::System(s) => s.channel_type == ChannelType::Group && &s.target_id == id,
+                Event::Unknown(_) => false,
+            },
+            Self::Channel(id) => {
+                message.is_some_and(|m| m.channel_type == ChannelType::Group && &m.target_id == id)
+            }
+            Self::Author(id) => message.is_some_and(|m| &m.author_id == id),
+            Self::MessageType(t) => message.is_some_and(|m| m.message_type == *t),
+            Self::NotBot => message.is_none_or(|m| !m.is_from_bot()),
+            Self::Mentions(id) => message.is_some_and(|m| m.extra.mention.contains(id)),
+            Self::MentionsMe => match (message, me) {
+                (Some(m), Some(me)) => m.extra.mention.iter().any(|id| id == me),
+                _ => false,
+            },
+            Self::Content(re) => message.is_some_and(|m| re.is_match(&m.content)),
+            Self::Custom(f) => f(event),
+            Self::And(a, b) => a.matches(event, me) && b.matches(event, me),
+            Self::Or(a, b) => a.matches(event, me) || b.matches(event, me),
+            Self::Not(a) => !a.matches(event, me),
+        }
+    }
+
+    /// filter events in a stream, errors and notices are always passed
+    pub fn apply<S>(self, stream: S) -> FilterStream<S> {
+        FilterStream {
+            stream,
+            filter: self,
+            me: None,
+        }
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// A handler which only receives events passing the filter
+#[derive(Debug)]
+pub struct Filtered<H> {
+    filter: Filter,
+    handler: H,
+}
+
+impl<H: Handler> Filtered<H> {
+    /// wrap a handler with filter
+    pub fn new(filter: Filter, handler: H) -> Self {
+        Self { filter, handler }
+    }
+}
+
+impl<H: Handler> Handler for Filtered<H> {
+    fn handle(&self, ctx: Context, event: Event) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let me = if self.filter.needs_me() {
+                match ctx.me().await {
+                    Ok(me) => Some(me.id.clone()),
+                    Err(err) => {
+                        log::warn!("Get bot user info failed when apply filter: {}", err);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            if self.filter.matches(&event, me.as_deref()) {
+                self.handler.handle(ctx, event).await;
+            }
+        })
+    }
+
+    fn handle_notice(&self, ctx: Context, notice: Notice) -> BoxFuture<'_, ()> {
+        self.handler.handle_notice(ctx, notice)
+    }
+}
+
+/// Stream adapter which only yields events passing the filter, notices are always passed
+#[derive(Debug)]
+pub struct FilterStream<S> {
+    stream: S,
+    filter: Filter,
+    me: Option<String>,
+}
+
+impl<S> FilterStream<S> {
+    /// set bot's own user id, used by [`Filter::MentionsMe`]
+    pub fn with_me<T: Into<String>>(mut self, id: T) -> Self {
+        self.me.replace(id.into());
+        self
+    }
+}
+
+impl<S, E> Stream for FilterStream<S>
+where
+    S: Stream<Item = Result<EventStreamItem, E>> + Unpin,
+{
+    type Item = Result<EventStreamItem, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(EventStreamItem::Event(event)))) => {
+                    if self.filter.matches(&event, self.me.as_deref()) {
+                        return Poll::Ready(Some(Ok(EventStreamItem::Event(event))));
+                    }
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn message(content: &str, bot: bool, mention: &[&str]) -> Event {
+        serde_json::from_value(json!({
+            "channel_type": "GROUP",
+            "type": 9,
+            "target_id": "channel",
+            "author_id": "author",
+            "content": content,
+            "msg_id": "xxxxx",
+            "msg_timestamp": 1607680283000u64,
+            "extra": {
+                "type": 9,
+                "guild_id": "guild",
+                "mention": mention,
+                "author": { "id": "author", "username": "someone", "bot": bot },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_basic() {
+        let event = message("hello", false, &["me"]);
+
+        assert!(Filter::guild("guild").matches(&event, None));
+        assert!(Filter::channel("channel").matches(&event, None));
+        assert!(!Filter::author("other").matches(&event, None));
+        assert!(Filter::MessageType(MessageType::KMarkdown).matches(&event, None));
+        assert!(Filter::NotBot.matches(&event, None));
+        assert!(!Filter::NotBot.matches(&message("hello", true, &[]), None));
+        assert!(Filter::MentionsMe.matches(&event, Some("me")));
+        assert!(!Filter::MentionsMe.matches(&event, None));
+        assert!(Filter::content(Regex::new("^hel+o$").unwrap()).matches(&event, None));
+    }
+
+    #[test]
+    fn test_filter_combine() {
+        let event = message("hello", false, &[]);
+
+        let filter = Filter::guild("guild").and(!Filter::author("author"));
+        assert!(!filter.matches(&event, None));
+
+        let filter = filter.or(Filter::custom(|e| e.msg_id() == Some("xxxxx")));
+        assert!(filter.matches(&event, None));
+        assert!(!filter.needs_me());
+        assert!((!Filter::MentionsMe).needs_me());
+    }
+}
@@ -0,0 +1,260 @@
+//! event handler
+
+use std::{future::Future, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use tokio::{
+    sync::{mpsc, OnceCell},
+    task::JoinHandle,
+};
+
+use crate::{
+    api::{
+        self,
+        types::{ChatTarget, CreateDirectMessage, CreateMessage, CreatedMessage, User},
+    },
+    ws::{
+        client::{EventStreamItem, Notice},
+        event::{ChannelType, MessageEvent, MessageType},
+        Event,
+    },
+};
+
+/// how many items can wait for handlers before receiving from event source is paused
+const DISPATCH_CHANNEL_CAPACITY: usize = 32;
+
+/// Context passed to event handlers
+#[derive(Debug, Clone)]
+pub struct Context {
+    api_client: Arc<api::Client>,
+    me: Arc<OnceCell<User>>,
+}
+
+impl Context {
+    pub(crate) fn new(api_client: Arc<api::Client>) -> Self {
+        Self {
+            api_client,
+            me: Arc::default(),
+        }
+    }
+
+    /// get the shared kaiheila api client
+    pub fn api(&self) -> &api::Client {
+        &self.api_client
+    }
+
+    /// get user info of the bot itself, fetched from api at first call and cached
+    pub async fn me(&self) -> api::Result<&User> {
+        self.me.get_or_try_init(|| self.api_client.me()).await
+    }
+
+    /// reply a text message to the message, quoting it.
+    ///
+    /// channel message is replied in the channel, private message is replied in the private chat.
+    pub async fn reply<S: Into<String>>(
+        &self,
+        message: &MessageEvent,
+        content: S,
+    ) -> api::Result<CreatedMessage> {
+        if message.channel_type == ChannelType::Person {
+            let target = match message.extra.code {
+                Some(ref code) => ChatTarget::ChatCode(code.clone()),
+                None => ChatTarget::TargetId(message.author_id.clone()),
+            };
+
+            let reply = CreateDirectMessage::new(target, content)
+                .message_type(MessageType::Text)
+                .quote(&message.msg_id);
+
+            self.api_client.create_direct_message(&reply).await
+        } else {
+            let reply = CreateMessage::new(&message.target_id, content)
+                .message_type(MessageType::Text)
+                .quote(&message.msg_id);
+
+            self.api_client.create_message(&reply).await
+        }
+    }
+}
+
+/// Event handler
+///
+/// Any `Fn(Context, Event) -> impl Future<Output = ()>` closure is a handler.
+pub trait Handler: Send + Sync {
+    /// handle an event
+    fn handle(&self, ctx: Context, event: Event) -> BoxFuture<'_, ()>;
+
+    /// handle an event stream lifecycle notice, ignored by default
+    fn handle_notice(&self, _ctx: Context, _notice: Notice) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+impl<F, Fut> Handler for F
+where
+    F: Fn(Context, Event) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn handle(&self, ctx: Context, event: Event) -> BoxFuture<'_, ()> {
+        Box::pin(self(ctx, event))
+    }
+}
+
+/// A list of handlers, dispatch every event to all of them in registration order
+#[derive(Clone, Default)]
+pub(crate) struct Handlers {
+    handlers: Vec<Arc<dyn Handler>>,
+}
+
+impl std::fmt::Debug for Handlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handlers")
+            .field("count", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl Handlers {
+    pub fn push<H: Handler + 'static>(&mut self, handler: H) {
+        self.handlers.push(Arc::new(handler));
+    }
+
+    /// Start a background task which dispatches items to handlers one by one.
+    ///
+    /// Every handler finishes an item before any handler gets the next one, so handlers see
+    /// events and notices in the same order as the event stream. A panicking handler only
+    /// loses the item it was handling.
+    pub fn start(self, ctx: Context) -> Dispatcher {
+        let (tx, mut rx) = mpsc::channel::<EventStreamItem>(DISPATCH_CHANNEL_CAPACITY);
+
+        let task = tokio::spawn(async move {
+            while let Some(item) = rx.recv().await {
+                if self.handlers.is_empty() {
+                    log::trace!("No handler registered, drop item");
+                    continue;
+                }
+
+                for handler in self.handlers.iter() {
+                    let handler = Arc::clone(handler);
+                    let ctx = ctx.clone();
+                    let item = item.clone();
+
+                    // run in its own task so a panic doesn't stop the dispatch loop
+                    let result = tokio::spawn(async move {
+                        match item {
+                            EventStreamItem::Event(event) => handler.handle(ctx, event).await,
+                            EventStreamItem::Notice(notice) => {
+                                handler.handle_notice(ctx, notice).await
+                            }
+                        }
+                    })
+                    .await;
+
+                    if let Err(err) = result {
+                        log::error!("Handler failed: {}", err);
+                    }
+                }
+            }
+        });
+
+        Dispatcher { tx, task }
+    }
+}
+
+/// Sending side of the background dispatch task
+#[derive(Debug)]
+pub(crate) struct Dispatcher {
+    tx: mpsc::Sender<EventStreamItem>,
+    task: JoinHandle<()>,
+}
+
+impl Dispatcher {
+    /// Queue an item for handlers, waits if handlers are behind.
+    ///
+    /// Returns false if the dispatch task stopped.
+    pub async fn dispatch(&self, item: EventStreamItem) -> bool {
+        if self.tx.send(item).await.is_err() {
+            log::error!("Dispatch task stopped, drop item");
+            return false;
+        }
+        true
+    }
+
+    /// Wait all dispatched items to be handled
+    pub async fn finish(self) {
+        drop(self.tx);
+        if let Err(err) = self.task.await {
+            log::error!("Dispatch task failed: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dispatch_in_order() {
+        let (handled_tx, mut handled_rx) = mpsc::unbounded_channel();
+
+        let mut handlers = Handlers::default();
+        handlers.push(move |_ctx, event: Event| {
+            let handled_tx = handled_tx.clone();
+            async move {
+                let sn = event.as_unknown().unwrap()["sn"].as_u64().unwrap();
+                // earlier events take longer, they would finish last without ordering
+                tokio::time::sleep(Duration::from_millis(10 - sn)).await;
+                handled_tx.send(sn).unwrap();
+            }
+        });
+
+        let ctx = Context::new(Arc::new(api::Client::new_from_bot_token("token").unwrap()));
+        let dispatcher = handlers.start(ctx);
+
+        for sn in 1..=5 {
+            let event = Event::Unknown(serde_json::json!({ "sn": sn }));
+            assert!(dispatcher.dispatch(EventStreamItem::Event(event)).await);
+        }
+        dispatcher.finish().await;
+
+        let mut handled = Vec::new();
+        while let Ok(sn) = handled_rx.try_recv() {
+            handled.push(sn);
+        }
+
+        assert_eq!(handled, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_after_handler_panic() {
+        let (handled_tx, mut handled_rx) = mpsc::unbounded_channel();
+
+        let mut handlers = Handlers::default();
+        handlers.push(move |_ctx, event: Event| {
+            let handled_tx = handled_tx.clone();
+            async move {
+                let sn = event.as_unknown().unwrap()["sn"].as_u64().unwrap();
+                assert_ne!(sn, 2, "handler panics on event 2");
+                handled_tx.send(sn).unwrap();
+            }
+        });
+
+        let ctx = Context::new(Arc::new(api::Client::new_from_bot_token("token").unwrap()));
+        let dispatcher = handlers.start(ctx);
+
+        for sn in 1..=3 {
+            let event = Event::Unknown(serde_json::json!({ "sn": sn }));
+            assert!(dispatcher.dispatch(EventStreamItem::Event(event)).await);
+        }
+        dispatcher.finish().await;
+
+        let mut handled = Vec::new();
+        while let Ok(sn) = handled_rx.try_recv() {
+            handled.push(sn);
+        }
+
+        assert_eq!(handled, vec![1, 3]);
+    }
+}
@@ -8,10 +8,17 @@
 #![forbid(unsafe_code)]
 
 pub mod api;
+pub mod backoff;
+pub mod command;
+pub mod filter;
+pub mod source;
+pub mod webhook;
 pub mod ws;
 
 mod bot;
 mod error;
+mod handler;
 
 pub use bot::Bot;
 pub use error::{Error, Result};
+pub use handler::{Context, Handler};
@@ -0,0 +1,90 @@
+//! event sources which [Bot](crate::Bot) receives events from
+
+mod replay;
+mod webhook;
+mod websocket;
+
+pub use replay::ReplaySource;
+pub use websocket::WebsocketSource;
+
+use std::fmt::Debug;
+
+use futures_util::future::BoxFuture;
+use tokio::sync::mpsc;
+
+use crate::{
+    api::types::GatewayResumeArguments,
+    ws::client::{EventStream, EventStreamError, EventStreamItem},
+};
+
+/// Where events come from, like websocket gateway or webhook server
+pub trait EventSource: Debug + Send + Sync {
+    /// Open a new event stream.
+    ///
+    /// `resume` is the resume arguments of last broken stream if it can be resumed,
+    /// sources which don't support resuming just ignore it.
+    /// The stream ending without error means the source is exhausted.
+    fn open(
+        &mut self,
+        resume: Option<GatewayResumeArguments>,
+    ) -> BoxFuture<'_, crate::Result<EventStream>>;
+}
+
+/// In-memory event source, events are pushed by the sender side, mainly for testing
+#[derive(Debug)]
+pub struct ChannelSource {
+    rx: Option<mpsc::Receiver<Result<EventStreamItem, EventStreamError>>>,
+}
+
+/// Sender side of [ChannelSource]
+pub type ChannelSender = mpsc::Sender<Result<EventStreamItem, EventStreamError>>;
+
+impl ChannelSource {
+    /// Create a channel source with given buffer size
+    pub fn new(capacity: usize) -> (Self, ChannelSender) {
+        let (tx, rx) = mpsc::channel(capacity);
+        (Self { rx: Some(rx) }, tx)
+    }
+}
+
+impl EventSource for ChannelSource {
+    fn open(
+        &mut self,
+        _resume: Option<GatewayResumeArguments>,
+    ) -> BoxFuture<'_, crate::Result<EventStream>> {
+        // channel can only be consumed once, later streams end immediately
+        let rx = self.rx.take().unwrap_or_else(|| mpsc::channel(1).1);
+        Box::pin(async move { Ok(EventStream::new(rx, None)) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::ws::Event;
+
+    #[tokio::test]
+    async fn test_channel_source_open_once() {
+        let (mut source, sender) = ChannelSource::new(4);
+
+        let event = Event::Unknown(serde_json::json!({ "hello": "world" }));
+        sender
+            .send(Ok(EventStreamItem::Event(event.clone())))
+            .await
+            .unwrap();
+        drop(sender);
+
+        let mut stream = source.open(None).await.unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap().into_event(),
+            Ok(event)
+        );
+        assert!(stream.next().await.is_none());
+
+        // consumed, later streams end immediately
+        let mut stream = source.open(None).await.unwrap();
+        assert!(stream.next().await.is_none());
+    }
+}
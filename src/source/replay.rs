@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+
+use futures_util::future::BoxFuture;
+use snafu::prelude::*;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    sync::mpsc,
+};
+
+use super::EventSource;
+use crate::{
+    api::types::GatewayResumeArguments,
+    error,
+    ws::{
+        client::{EventStream, EventStreamItem},
+        event::EventData,
+    },
+    Result,
+};
+
+/// Replay events recorded in a JSONL file, one `{"sn": .., "d": {..}}` event per line.
+///
+/// Lines which can't be parsed are skipped, the stream ends at end of file.
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    path: PathBuf,
+    replayed: bool,
+}
+
+impl ReplaySource {
+    /// Create a replay source reading given file
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            replayed: false,
+        }
+    }
+}
+
+impl EventSource for ReplaySource {
+    fn open(
+        &mut self,
+        _resume: Option<GatewayResumeArguments>,
+    ) -> BoxFuture<'_, Result<EventStream>> {
+        Box::pin(async move {
+            let (tx, rx) = mpsc::channel(32);
+
+            // file is only replayed once, later streams end immediately
+            if self.replayed {
+                return Ok(EventStream::new(rx, None));
+            }
+
+            let file = tokio::fs::File::open(&self.path)
+                .await
+                .with_context(|_| error::ReadReplayFileFailed { path: &self.path })?;
+            self.replayed = true;
+
+            let path = self.path.clone();
+
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(file).lines();
+                let mut line_number = 0;
+
+                loop {
+                    line_number += 1;
+
+                    let line = match lines.next_line().await {
+                        Ok(Some(line)) => line,
+                        Ok(None) => break,
+                        Err(err) => {
+                            log::warn!("Read replay file {} failed: {}", path.display(), err);
+                            break;
+                        }
+                    };
+
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let data: EventData = match serde_json::from_str(&line) {
+                        Ok(data) => data,
+                        Err(err) => {
+                            log::warn!("Skip invalid line {} in replay file: {}", line_number, err);
+                            continue;
+                        }
+                    };
+
+                    if tx
+                        .send(Ok(EventStreamItem::Event(data.event)))
+                        .await
+                        .is_err()
+                    {
+                        log::debug!("Event stream receive side dropped, stop replay");
+                        break;
+                    }
+                }
+
+                log::debug!("Replay file {} finished", path.display());
+            });
+
+            Ok(EventStream::new(rx, None))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::Error;
+
+    #[tokio::test]
+    async fn test_replay_after_open_failed() {
+        let path = std::env::temp_dir().join(format!("burz-replay-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut source = ReplaySource::new(&path);
+
+        let result = source.open(None).await;
+        assert!(matches!(result, Err(Error::ReadReplayFileFailed { .. })));
+
+        std::fs::write(&path, "{\"sn\": 1, \"d\": {\"sn\": 1}}\n").unwrap();
+
+        let mut stream = source.open(None).await.unwrap();
+        let event = stream.next().await.unwrap().unwrap().into_event().unwrap();
+        assert_eq!(event.as_unknown().unwrap()["sn"], 1);
+        assert!(stream.next().await.is_none());
+
+        // replayed, later streams end immediately
+        let mut stream = source.open(None).await.unwrap();
+        assert!(stream.next().await.is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+use futures_util::future::BoxFuture;
+use snafu::prelude::*;
+
+use super::EventSource;
+use crate::{
+    api::types::GatewayResumeArguments, error, webhook::Server, ws::client::EventStream, Result,
+};
+
+impl EventSource for Server {
+    fn open(
+        &mut self,
+        _resume: Option<GatewayResumeArguments>,
+    ) -> BoxFuture<'_, Result<EventStream>> {
+        Box::pin(async move {
+            self.clone()
+                .run()
+                .await
+                .context(error::StartWebhookServerFailed)
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use snafu::prelude::*;
+
+use super::EventSource;
+use crate::{
+    api::{
+        self,
+        types::{GatewayResumeArguments, GatewayURLInfo},
+    },
+    error,
+    ws::{
+        self,
+        client::{ClientConfig, EventStream},
+    },
+    Result,
+};
+
+/// Receive events from kaiheila websocket gateway
+#[derive(Debug)]
+pub struct WebsocketSource {
+    api_client: Arc<api::Client>,
+    gateway_override: Option<GatewayURLInfo>,
+    config: ClientConfig,
+}
+
+impl WebsocketSource {
+    /// Create a websocket source, gateway url is fetched using the api client
+    pub fn new(api_client: Arc<api::Client>) -> Self {
+        Self {
+            api_client,
+            gateway_override: None,
+            config: ClientConfig::default(),
+        }
+    }
+
+    /// Set timing and capacity settings of the websocket client
+    pub fn with_config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Always connect to given gateway url instead of fetching one from `/gateway/index`.
+    ///
+    /// This is mainly used for testing with a local fake gateway.
+    pub fn with_gateway_url<S: AsRef<str> + ?Sized>(mut self, url: &S) -> Result<Self> {
+        let url = url.as_ref();
+        let gateway = url
+            .parse()
+            .with_context(|_| error::InvalidGatewayURL { url })?;
+
+        self.gateway_override.replace(gateway);
+
+        Ok(self)
+    }
+
+    async fn fetch_new_gateway(&self) -> Result<GatewayURLInfo> {
+        if let Some(ref gateway) = self.gateway_override {
+            log::debug!("Use overridden gateway url");
+            return Ok(gateway.clone());
+        }
+
+        log::info!("Getting gateway url ...");
+
+        let gateway_url = self
+            .api_client
+            .gateway_url()
+            .await
+            .context(error::CallAPIFailed)?;
+
+        gateway_url
+            .parse()
+            .with_context(|_| error::InvalidGatewayURL { url: &gateway_url })
+    }
+}
+
+impl EventSource for WebsocketSource {
+    fn open(
+        &mut self,
+        resume: Option<GatewayResumeArguments>,
+    ) -> BoxFuture<'_, Result<EventStream>> {
+        Box::pin(async move {
+            let gateway_info = self.fetch_new_gateway().await?;
+
+            log::debug!("Got gateway url: {}", gateway_info.url());
+
+            let ws_client = if let Some(r) = resume {
+                log::debug!("Resume conversion using argument: {:?}", r);
+                ws::Client::resume(r)
+            } else {
+                ws::Client::new()
+            };
+
+            ws_client
+                .with_config(self.config.clone())
+                .run(gateway_info)
+                .await
+                .context(error::RunWebsocketClientFailed)
+        })
+    }
+}
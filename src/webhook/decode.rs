@@ -0,0 +1,209 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bytes::Bytes;
+use serde::Deserialize;
+use snafu::prelude::*;
+
+use crate::ws::{
+    event::EventData,
+    message::{Message, ParseMessageError},
+};
+
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+
+static CHALLENGE_CHANNEL_TYPE: &str = "WEBHOOK_CHALLENGE";
+
+/// Error when decode webhook request body
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), module(error), context(suffix(false)))]
+pub enum DecodeError {
+    /// decompress or parse body as message failed
+    #[snafu(display("parse webhook message failed: {source}"))]
+    ParseMessageFailed {
+        /// source error
+        source: ParseMessageError,
+    },
+
+    /// body is not valid json
+    #[snafu(display("parse webhook body as json failed: {source}"))]
+    ParseJSONFailed {
+        /// source error
+        source: serde_json::Error,
+    },
+
+    /// body is encrypted but no encrypt key configured
+    #[snafu(display("webhook body is encrypted but no encrypt key configured"))]
+    NoEncryptKey,
+
+    /// encrypted body is not valid base64
+    #[snafu(display("encrypted webhook body is not valid base64: {source}"))]
+    InvalidBase64 {
+        /// source error
+        source: base64::DecodeError,
+    },
+
+    /// encrypted body is too short to contain iv
+    #[snafu(display("encrypted webhook body is too short"))]
+    EncryptedBodyTooShort,
+
+    /// decrypt body failed, usually means encrypt key is wrong
+    #[snafu(display("decrypt webhook body failed, encrypt key may be wrong"))]
+    DecryptFailed,
+
+    /// verify token in body does not match configured one
+    #[snafu(display("webhook verify token mismatch"))]
+    VerifyTokenMismatch,
+
+    /// body is not a challenge or event message
+    #[snafu(display("webhook message is not event"))]
+    NotEvent,
+}
+
+/// Decoded webhook request
+#[derive(Debug)]
+pub(crate) enum Payload {
+    /// challenge handshake, should respond the challenge back
+    Challenge(String),
+    /// event pushed by server
+    Event(EventData),
+}
+
+#[derive(Deserialize)]
+struct Encrypted {
+    encrypt: String,
+}
+
+#[derive(Deserialize)]
+struct Verify {
+    #[serde(default)]
+    verify_token: String,
+    #[serde(default)]
+    channel_type: String,
+    #[serde(default)]
+    challenge: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyData {
+    d: Verify,
+}
+
+/// Decode pipeline of webhook body: inflate, decrypt, verify, then parse
+#[derive(Debug, Clone)]
+pub(crate) struct Decoder {
+    pub verify_token: String,
+    pub encrypt_key: Option<String>,
+}
+
+impl Decoder {
+    pub fn decode(&self, mut body: Bytes) -> Result<Payload, DecodeError> {
+        // plain json always starts with `{`, otherwise it's zlib compressed
+        if body.first() != Some(&b'{') {
+            body = Message::inflate(body).context(error::ParseMessageFailed)?;
+        }
+
+        if let Ok(encrypted) = serde_json::from_slice::<Encrypted>(&body) {
+            let key = self.encrypt_key.as_deref().context(error::NoEncryptKey)?;
+            body = decrypt(key, &encrypted.encrypt)?;
+        }
+
+        let verify: VerifyData = serde_json::from_slice(&body).context(error::ParseJSONFailed)?;
+
+        ensure!(
+            verify.d.verify_token == self.verify_token,
+            error::VerifyTokenMismatch
+        );
+
+        if verify.d.channel_type == CHALLENGE_CHANNEL_TYPE {
+            return Ok(Payload::Challenge(verify.d.challenge));
+        }
+
+        Message::decode(body, false)
+            .context(error::ParseMessageFailed)?
+            .into_event()
+            .map(Payload::Event)
+            .map_err(|_| error::NotEvent.build())
+    }
+}
+
+/// Decrypt kaiheila encrypted data.
+///
+/// `data` is base64 of `iv(16 bytes) + base64(ciphertext)`, key is `encrypt_key` padded with
+/// zero to 32 bytes.
+fn decrypt(key: &str, data: &str) -> Result<Bytes, DecodeError> {
+    let data = BASE64.decode(data).context(error::InvalidBase64)?;
+    ensure!(data.len() > 16, error::EncryptedBodyTooShort);
+
+    let (iv, ciphertext) = data.split_at(16);
+    let ciphertext = BASE64.decode(ciphertext).context(error::InvalidBase64)?;
+
+    let mut padded_key = [0u8; 32];
+    let len = key.len().min(32);
+    padded_key[..len].copy_from_slice(&key.as_bytes()[..len]);
+
+    Aes256CbcDec::new(&padded_key.into(), iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+        .map(Into::into)
+        .map_err(|_| error::DecryptFailed.build())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+    use serde_json::json;
+
+    fn encrypt(key: &str, iv: &[u8; 16], plain: &[u8]) -> String {
+        let mut padded_key = [0u8; 32];
+        padded_key[..key.len()].copy_from_slice(key.as_bytes());
+
+        let ciphertext = cbc::Encryptor::<aes::Aes256>::new(&padded_key.into(), iv.into())
+            .encrypt_padded_vec_mut::<Pkcs7>(plain);
+
+        let mut data = iv.to_vec();
+        data.extend(BASE64.encode(ciphertext).into_bytes());
+        BASE64.encode(data)
+    }
+
+    fn decoder() -> Decoder {
+        Decoder {
+            verify_token: "token".to_string(),
+            encrypt_key: Some("key".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_decode_encrypted_challenge() {
+        let plain = serde_json::to_vec(&json!({
+            "s": 0,
+            "d": {
+                "type": 255,
+                "channel_type": "WEBHOOK_CHALLENGE",
+                "challenge": "bkyk",
+                "verify_token": "token",
+            },
+        }))
+        .unwrap();
+
+        let body = serde_json::to_vec(&json!({
+            "encrypt": encrypt("key", b"0123456789abcdef", &plain),
+        }))
+        .unwrap();
+
+        let payload = decoder().decode(body.into()).unwrap();
+        assert!(matches!(payload, Payload::Challenge(c) if c == "bkyk"));
+    }
+
+    #[test]
+    fn test_decode_verify_token_mismatch() {
+        let body = serde_json::to_vec(&json!({
+            "s": 0,
+            "sn": 1,
+            "d": { "type": 1, "verify_token": "other" },
+        }))
+        .unwrap();
+
+        let err = decoder().decode(body.into()).unwrap_err();
+        assert!(matches!(err, DecodeError::VerifyTokenMismatch));
+    }
+}
@@ -0,0 +1,315 @@
+//! Kaiheila webhook event source, receive events pushed to a local HTTP server.
+//!
+//! see <https://developer.kaiheila.cn/doc/webhook>
+
+mod decode;
+
+pub use decode::DecodeError;
+
+use std::{
+    collections::{HashSet, VecDeque},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, StatusCode,
+};
+use snafu::prelude::*;
+use tokio::sync::mpsc;
+
+use crate::{
+    api::types::GatewayResumeArguments,
+    ws::client::{EventStream, EventStreamError, EventStreamErrorKind, EventStreamItem},
+};
+use decode::{Decoder, Payload};
+
+/// how many recent sn are remembered for dedup
+const DEDUP_SN_COUNT: usize = 1024;
+
+/// Error when start webhook server
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), module(error), context(suffix(false)))]
+pub enum WebhookError {
+    /// bind listen address failed
+    #[snafu(display("bind webhook server to {addr} failed: {source}"))]
+    BindFailed {
+        /// listen address
+        addr: SocketAddr,
+        /// source error
+        source: hyper::Error,
+    },
+}
+
+/// Webhook HTTP server, yields events pushed by kaiheila
+#[derive(Debug, Clone)]
+pub struct Server {
+    addr: SocketAddr,
+    path: String,
+    decoder: Decoder,
+}
+
+impl Server {
+    /// Create a webhook server listening on `addr`, `verify_token` is shown in bot settings
+    pub fn new<S: Into<String>>(addr: SocketAddr, verify_token: S) -> Self {
+        Self {
+            addr,
+            path: "/".to_string(),
+            decoder: Decoder {
+                verify_token: verify_token.into(),
+                encrypt_key: None,
+            },
+        }
+    }
+
+    /// Set callback url path, default is `/`
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set encrypt key, required if message encryption is enabled in bot settings
+    pub fn encrypt_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.decoder.encrypt_key.replace(key.into());
+        self
+    }
+
+    /// Start the server in background, returning a stream for kaiheila event.
+    ///
+    /// The server stops when the stream is dropped.
+    pub async fn run(self) -> Result<EventStream, WebhookError> {
+        let (event_tx, event_rx) = mpsc::channel(32);
+
+        let state = Arc::new(State {
+            path: self.path,
+            decoder: self.decoder,
+            event_tx: event_tx.clone(),
+            seen: Mutex::default(),
+        });
+
+        let make_service = make_service_fn(move |_| {
+            let state = Arc::clone(&state);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let state = Arc::clone(&state);
+                    async move { Ok::<_, Infallible>(state.handle(req).await) }
+                }))
+            }
+        });
+
+        let closed_tx = event_tx.clone();
+        let server = hyper::Server::try_bind(&self.addr)
+            .context(error::BindFailed { addr: self.addr })?
+            .serve(make_service)
+            .with_graceful_shutdown(async move { closed_tx.closed().await });
+
+        log::info!("Webhook server listening on {}", self.addr);
+
+        tokio::spawn(async move {
+            if let Err(err) = server.await {
+                log::warn!("Webhook server stopped with error: {}", err);
+                let _ = event_tx
+                    .send(Err(EventStreamError {
+                        resume: GatewayResumeArguments::default(),
+                        source: EventStreamErrorKind::WebhookServerFailed { source: err },
+                    }))
+                    .await;
+            } else {
+                log::debug!("Webhook server stopped");
+            }
+        });
+
+        Ok(EventStream::new(event_rx, None))
+    }
+}
+
+/// Recently received sn, webhook may deliver one event more than once
+#[derive(Debug, Default)]
+struct SeenSn {
+    set: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl SeenSn {
+    fn insert(&mut self, sn: u64) -> bool {
+        if !self.set.insert(sn) {
+            return false;
+        }
+
+        self.order.push_back(sn);
+        if self.order.len() > DEDUP_SN_COUNT {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    path: String,
+    decoder: Decoder,
+    event_tx: mpsc::Sender<Result<EventStreamItem, EventStreamError>>,
+    seen: Mutex<SeenSn>,
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    let mut resp = Response::new(Body::empty());
+    *resp.status_mut() = code;
+    resp
+}
+
+impl State {
+    async fn handle(&self, req: Request<Body>) -> Response<Body> {
+        if req.method() != Method::POST || req.uri().path() != self.path {
+            return status(StatusCode::NOT_FOUND);
+        }
+
+        let body = match hyper::body::to_bytes(req.into_body()).await {
+            Ok(body) => body,
+            Err(err) => {
+                log::warn!("Read webhook request body failed: {}", err);
+                return status(StatusCode::BAD_REQUEST);
+            }
+        };
+
+        match self.decoder.decode(body) {
+            Ok(Payload::Challenge(challenge)) => {
+                log::info!("Webhook challenge received");
+                let body = serde_json::json!({ "challenge": challenge }).to_string();
+                Response::new(Body::from(body))
+            }
+            Ok(Payload::Event(data)) => {
+                if !self.seen.lock().unwrap().insert(data.sn) {
+                    log::trace!("Duplicated event {} received, drop it", data.sn);
+                } else if self
+                    .event_tx
+                    .send(Ok(EventStreamItem::Event(data.event)))
+                    .await
+                    .is_err()
+                {
+                    log::debug!("Event stream receive side dropped, drop event {}", data.sn);
+                }
+                status(StatusCode::OK)
+            }
+            Err(err @ DecodeError::VerifyTokenMismatch) => {
+                log::warn!("Reject webhook request: {}", err);
+                status(StatusCode::FORBIDDEN)
+            }
+            Err(err) => {
+                log::warn!("Decode webhook request failed: {}", err);
+                status(StatusCode::BAD_REQUEST)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+
+    async fn start() -> (String, EventStream) {
+        // find a free port for the server
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let stream = Server::new(addr, "token")
+            .path("/webhook")
+            .encrypt_key("key")
+            .run()
+            .await
+            .unwrap();
+
+        (format!("http://{addr}/webhook"), stream)
+    }
+
+    async fn post(url: &str, body: impl Into<reqwest::Body>) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(url)
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    fn event(sn: u64) -> String {
+        json!({ "s": 0, "sn": sn, "d": { "verify_token": "token", "sn": sn } }).to_string()
+    }
+
+    fn event_sn(item: EventStreamItem) -> u64 {
+        item.into_event().unwrap().as_unknown().unwrap()["sn"]
+            .as_u64()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_challenge() {
+        let (url, _stream) = start().await;
+
+        let body = json!({
+            "s": 0,
+            "d": {
+                "type": 255,
+                "channel_type": "WEBHOOK_CHALLENGE",
+                "challenge": "bkyk",
+                "verify_token": "token",
+            },
+        });
+        let resp = post(&url, body.to_string()).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body, json!({ "challenge": "bkyk" }));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_mismatch() {
+        let (url, _stream) = start().await;
+
+        let body = json!({ "s": 0, "sn": 1, "d": { "verify_token": "other", "sn": 1 } });
+        let resp = post(&url, body.to_string()).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_drop_duplicated_event() {
+        let (url, mut stream) = start().await;
+
+        for sn in [1, 1, 2] {
+            assert_eq!(post(&url, event(sn)).await.status(), StatusCode::OK);
+        }
+
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 1);
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bad_request_keeps_server_running() {
+        let (url, mut stream) = start().await;
+
+        // neither json nor zlib
+        let resp = post(&url, "not a message").await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // encrypted body which can not be decrypted
+        let resp = post(
+            &url,
+            json!({ "encrypt": "bm90IGVuY3J5cHRlZA==" }).to_string(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(post(&url, event(1)).await.status(), StatusCode::OK);
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 1);
+    }
+}
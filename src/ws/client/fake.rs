@@ -0,0 +1,128 @@
+//! local fake websocket gateway for tests
+
+use std::collections::HashMap;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::{
+    tungstenite::{handshake::server, Message},
+    WebSocketStream,
+};
+
+use crate::api::types::GatewayURLInfo;
+
+/// A fake gateway, every accepted connection is handed to the test
+#[derive(Debug)]
+pub(crate) struct FakeGateway {
+    url: String,
+    conns: mpsc::UnboundedReceiver<Connection>,
+}
+
+impl FakeGateway {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "ws://{}/gateway?compress=0&token=test",
+            listener.local_addr().unwrap()
+        );
+        let (tx, conns) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut query = HashMap::new();
+                let callback = QueryRecorder(&mut query);
+                let ws = match tokio_tungstenite::accept_hdr_async(stream, callback).await {
+                    Ok(ws) => ws,
+                    Err(_) => continue,
+                };
+                if tx.send(Connection { query, ws }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { url, conns }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn gateway(&self) -> GatewayURLInfo {
+        self.url.parse().unwrap()
+    }
+
+    pub async fn accept(&mut self) -> Connection {
+        self.conns.recv().await.unwrap()
+    }
+}
+
+/// Record query pairs of the websocket handshake request
+struct QueryRecorder<'a>(&'a mut HashMap<String, String>);
+
+impl server::Callback for QueryRecorder<'_> {
+    fn on_request(
+        self,
+        request: &server::Request,
+        response: server::Response,
+    ) -> Result<server::Response, server::ErrorResponse> {
+        let query = request.uri().query().unwrap_or_default();
+        *self.0 = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+        Ok(response)
+    }
+}
+
+/// A client connection of fake gateway
+#[derive(Debug)]
+pub(crate) struct Connection {
+    /// query pairs of the connecting url
+    pub query: HashMap<String, String>,
+    ws: WebSocketStream<TcpStream>,
+}
+
+impl Connection {
+    pub async fn send(&mut self, message: serde_json::Value) {
+        let data = message.to_string().into_bytes();
+        self.ws.send(Message::Binary(data)).await.unwrap();
+    }
+
+    pub async fn hello(&mut self, session_id: &str) {
+        self.send(json!({ "s": 1, "d": { "code": 0, "session_id": session_id } }))
+            .await;
+    }
+
+    pub async fn event(&mut self, sn: u64) {
+        self.send(json!({ "s": 0, "sn": sn, "d": { "sn": sn } }))
+            .await;
+    }
+
+    /// Receive next message sent by client, `None` if connection closed
+    pub async fn recv(&mut self) -> Option<serde_json::Value> {
+        loop {
+            match self.ws.next().await? {
+                Ok(Message::Binary(data)) => return serde_json::from_slice(&data).ok(),
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Answer pings until the client closes the connection
+    pub async fn pong_forever(mut self) {
+        while let Some(message) = self.recv().await {
+            if message["s"] == 2 {
+                self.send(json!({ "s": 3 })).await;
+            }
+        }
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.ws.close(None).await;
+    }
+}
@@ -0,0 +1,57 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use super::{
+    Backoff, EventBufferLimits, CONNECT_GATEWAY_MAX_ATTEMPTS, EVENT_CHANNEL_CAPACITY,
+    HELLO_TIMEOUT, PONG_TIMEOUT, RESUME_ACK_TIMEOUT, STREAMING_STATE_PING_INTERVAL,
+    STREAMING_STATE_PONG_TIMEOUT_MAX_COUNT, TIMEOUT_STATE_SEND_PING_INTERVAL_MAX,
+    TIMEOUT_STATE_SEND_PING_INTERVAL_START,
+};
+
+/// Timing and capacity settings of the websocket client state machine.
+///
+/// Defaults follow the official document, tests can shrink them to run the state machine fast.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// max time to wait the first hello message after connected
+    pub hello_timeout: Duration,
+    /// max time to wait a pong message after sent a ping
+    pub pong_timeout: Duration,
+    /// interval of sending ping in streaming state
+    pub ping_interval: Duration,
+    /// move to timeout state after this many pong timeouts in a row
+    pub pong_timeout_max_count: usize,
+    /// retry policy of connecting to gateway
+    pub connect_backoff: Backoff,
+    /// interval of sending ping in timeout state, the first ping is sent immediately
+    pub timeout_ping_backoff: Backoff,
+    /// max time to wait the ResumeACK message after resumed
+    pub resume_ack_timeout: Duration,
+    /// limits of the buffer which reorders out-of-order events
+    pub buffer_limits: EventBufferLimits,
+    /// capacity of the event stream channel
+    pub event_channel_capacity: NonZeroUsize,
+    /// send [Connected](super::Notice::Connected), [TimedOut](super::Notice::TimedOut) and
+    /// [Reconnecting](super::Notice::Reconnecting) notices to event stream
+    pub lifecycle_notices: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            hello_timeout: Duration::from_secs(HELLO_TIMEOUT),
+            pong_timeout: Duration::from_secs(PONG_TIMEOUT),
+            ping_interval: Duration::from_secs(STREAMING_STATE_PING_INTERVAL),
+            pong_timeout_max_count: STREAMING_STATE_PONG_TIMEOUT_MAX_COUNT,
+            connect_backoff: Backoff::fixed(Duration::ZERO)
+                .max_attempts(CONNECT_GATEWAY_MAX_ATTEMPTS),
+            timeout_ping_backoff: Backoff::exponential(
+                Duration::from_secs(TIMEOUT_STATE_SEND_PING_INTERVAL_START),
+                Duration::from_secs(TIMEOUT_STATE_SEND_PING_INTERVAL_MAX),
+            ),
+            resume_ack_timeout: Duration::from_secs(RESUME_ACK_TIMEOUT),
+            buffer_limits: EventBufferLimits::default(),
+            event_channel_capacity: NonZeroUsize::new(EVENT_CHANNEL_CAPACITY).unwrap(),
+            lifecycle_notices: false,
+        }
+    }
+}
@@ -4,12 +4,17 @@ use futures_util::{future, Sink, Stream, StreamExt};
 use snafu::prelude::*;
 use tokio::time::Instant;
 
-use super::{streaming::ClientStateStreaming, ClientInner, EventStream};
+use super::{
+    streaming::ClientStateStreaming, ClientConfig, ClientInner, EventStream, StatusReporter,
+};
 use crate::{
     api::types::GatewayURLInfo,
     ws::{
-        client::{inner::streaming::EventStreamSender, WebsocketClient},
-        message::{Message, MessageStreamSink, MessageStreamSinkError},
+        client::{
+            inner::streaming::{EventStreamSender, PendingResume},
+            WebsocketClient,
+        },
+        message::{HelloCode, Message, MessageStreamSink, MessageStreamSinkError},
     },
 };
 
@@ -29,16 +34,20 @@ pub enum WaitHelloError {
         source: Box<dyn std::error::Error + Send>,
     },
 
+    /// connection closed before hello received
+    #[snafu(display("connection closed before hello received"))]
+    ConnectionClosed,
+
     /// received first message is not hello type
     #[snafu(display("received first message is not hello"))]
     MessageNotHello,
 
     /// hello message code is not zero
     /// see <https://developer.kaiheila.cn/doc/websocket#%E4%BF%A1%E4%BB%A4[1]%20HELLO> for code meaning
-    #[snafu(display("hello message code {code} is not zero"))]
+    #[snafu(display("hello message code {code} is not success"))]
     HelloMessageCodeNotZero {
         /// status code
-        code: i64,
+        code: HelloCode,
     },
 
     /// received hello message has no session id
@@ -46,16 +55,29 @@ pub enum WaitHelloError {
     HelloMessageNoSessionId,
 }
 
+impl WaitHelloError {
+    /// if last session can still be resumed after this error
+    pub fn can_resume(&self) -> bool {
+        match self {
+            Self::HelloMessageCodeNotZero { code } => code.can_resume(),
+            _ => true,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ClientStateConnected {
     pub gateway: GatewayURLInfo,
     pub ws: WebsocketClient,
+    pub config: ClientConfig,
+    pub status: StatusReporter,
 }
 
 impl ClientInner<ClientStateConnected> {
     async fn real_wait_hello(
         ws: WebsocketClient,
         compress: bool,
+        timeout: Duration,
     ) -> Result<
         (
             impl Stream<Item = Result<Message, MessageStreamSinkError>>
@@ -76,7 +98,7 @@ impl ClientInner<ClientStateConnected> {
             future::ready(!skip)
         });
 
-        let deadline = Instant::now() + Duration::from_secs(6);
+        let deadline = Instant::now() + timeout;
 
         log::debug!("Waiting hello message, timeout tick: {:?}", deadline);
 
@@ -86,7 +108,7 @@ impl ClientInner<ClientStateConnected> {
                 return error::Timeout.fail();
             }
             result = message_stream.next() => {
-                result.unwrap().context(error::MessageStream)?
+                result.context(error::ConnectionClosed)?.context(error::MessageStream)?
             }
         };
 
@@ -99,7 +121,7 @@ impl ClientInner<ClientStateConnected> {
         log::debug!("Hello message data: {:?}", hello);
 
         ensure!(
-            hello.data.code == 0,
+            hello.data.code.is_success(),
             error::HelloMessageCodeNotZero {
                 code: hello.data.code
             }
@@ -114,16 +136,32 @@ impl ClientInner<ClientStateConnected> {
     }
 
     pub async fn wait_hello(mut self) -> Result<EventStream, WaitHelloError> {
-        let (message_stream, session_id) =
-            Self::real_wait_hello(self.state.ws, self.state.gateway.compress).await?;
+        let (message_stream, session_id) = Self::real_wait_hello(
+            self.state.ws,
+            self.state.gateway.compress,
+            self.state.config.hello_timeout,
+        )
+        .await?;
+
+        let pending_resume = PendingResume::new(
+            self.state.gateway.resume.as_ref(),
+            self.state.config.resume_ack_timeout,
+        );
 
-        let mut resume = self.state.gateway.resume.take().unwrap_or_default();
-        resume.session_id = session_id;
+        let resume = self.state.gateway.resume.take().unwrap_or_default();
 
-        log::debug!("New resume argument: {:?}", resume);
+        log::debug!(
+            "New session id: {}, resume argument: {:?}",
+            session_id,
+            resume
+        );
 
         let (sink, stream) = message_stream.split();
-        let (sender, event_stream) = EventStreamSender::new(resume);
+        let (mut sender, event_stream) =
+            EventStreamSender::new(resume, self.state.config, self.state.status);
+
+        // receive side is still here, sending can't fail
+        sender.on_connected(session_id).await;
 
         log::debug!("Move to streaming state");
 
@@ -131,6 +169,7 @@ impl ClientInner<ClientStateConnected> {
             state: ClientStateStreaming {
                 gateway: self.state.gateway,
                 sender,
+                pending_resume,
                 sink: Some(sink),
                 stream,
             },
@@ -140,28 +179,38 @@ impl ClientInner<ClientStateConnected> {
         Ok(event_stream)
     }
 
-    pub async fn re_wait_hello(mut self, sender: EventStreamSender) {
-        let (message_stream, session_id) =
-            match Self::real_wait_hello(self.state.ws, self.state.gateway.compress)
-                .await
-                .context(super::streaming::error::ReWaitHelloFailed)
-            {
-                Ok((m, s)) => (m, s),
-                Err(err) => {
-                    log::warn!(
-                        "Reconnect state wait hello failed: {}, send event stream error and stop",
-                        err
-                    );
-
-                    sender.send_err(err).await;
-                    return;
-                }
-            };
-
-        let mut resume = self.state.gateway.resume.take().unwrap_or_default();
-        resume.session_id = session_id;
-
-        log::debug!("New resume argument: {:?}", resume);
+    pub async fn re_wait_hello(self, mut sender: EventStreamSender) {
+        let (message_stream, session_id) = match Self::real_wait_hello(
+            self.state.ws,
+            self.state.gateway.compress,
+            self.state.config.hello_timeout,
+        )
+        .await
+        .context(super::streaming::error::ReWaitHelloFailed)
+        {
+            Ok((m, s)) => (m, s),
+            Err(err) => {
+                log::warn!(
+                    "Reconnect state wait hello failed: {}, send event stream error and stop",
+                    err
+                );
+
+                sender.send_err(err).await;
+                return;
+            }
+        };
+
+        let pending_resume = PendingResume::new(
+            self.state.gateway.resume.as_ref(),
+            self.state.config.resume_ack_timeout,
+        );
+
+        log::debug!("New session id: {}", session_id);
+
+        if !sender.on_connected(session_id).await {
+            log::debug!("Event stream receive side dropped, stop");
+            return;
+        }
 
         let (sink, stream) = message_stream.split();
 
@@ -171,6 +220,7 @@ impl ClientInner<ClientStateConnected> {
             state: ClientStateStreaming {
                 gateway: self.state.gateway,
                 sender,
+                pending_resume,
                 sink: Some(sink),
                 stream,
             },
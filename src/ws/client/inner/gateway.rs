@@ -1,7 +1,9 @@
 use snafu::*;
 use tokio_tungstenite as websocket;
 
-use super::{connected::ClientStateConnected, ClientInner};
+use super::{
+    connected::ClientStateConnected, status::ClientState, ClientConfig, ClientInner, StatusReporter,
+};
 use crate::api::types::GatewayURLInfo;
 
 /// Error when connect to websocket gateway
@@ -22,6 +24,8 @@ pub struct ConnectGatewayError {
 #[derive(Debug)]
 pub(crate) struct ClientStateGateway {
     pub gateway: GatewayURLInfo,
+    pub config: ClientConfig,
+    pub status: StatusReporter,
 }
 
 impl ClientInner<ClientStateGateway> {
@@ -30,10 +34,12 @@ impl ClientInner<ClientStateGateway> {
 
         log::debug!("Connecting gateway: {}", u);
 
+        let mut delays = self.state.config.connect_backoff.delays();
         let mut conn_result = websocket::connect_async(&u).await;
-        if conn_result.is_err() {
-            log::warn!("First try to connect gateway failed, start second try");
-            conn_result = websocket::connect_async(&u).await
+        while let (Err(err), Some(delay)) = (&conn_result, delays.next()) {
+            log::warn!("Connect gateway failed: {}, retry after {:?}", err, delay);
+            tokio::time::sleep(delay).await;
+            conn_result = websocket::connect_async(&u).await;
         }
 
         let ws = conn_result
@@ -41,11 +47,14 @@ impl ClientInner<ClientStateGateway> {
             .with_context(|_| error::ConnectGateway { url: u })?;
 
         log::debug!("Move to connected state");
+        self.state.status.set_state(ClientState::Connected);
 
         Ok(ClientInner {
             state: ClientStateConnected {
                 gateway: self.state.gateway,
                 ws,
+                config: self.state.config,
+                status: self.state.status,
             },
         })
     }
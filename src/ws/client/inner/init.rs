@@ -1,7 +1,8 @@
 use snafu::prelude::*;
 
 use super::{
-    gateway::ClientStateGateway, ClientInner, ConnectGatewayError, EventStream, WaitHelloError,
+    gateway::ClientStateGateway, status::ClientState, ClientConfig, ClientInner,
+    ConnectGatewayError, EventStream, StatusReporter, WaitHelloError,
 };
 use crate::api::types::{GatewayResumeArguments, GatewayURLInfo};
 
@@ -24,9 +25,21 @@ pub enum RunError {
     },
 }
 
+impl RunError {
+    /// if last session can still be resumed after this error
+    pub fn can_resume(&self) -> bool {
+        match self {
+            Self::ConnectGatewayFailed { .. } => true,
+            Self::WaitHelloFailed { source } => source.can_resume(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ClientStateInit {
     pub resume: Option<GatewayResumeArguments>,
+    pub config: ClientConfig,
+    pub status: StatusReporter,
 }
 
 impl ClientInner<ClientStateInit> {
@@ -51,9 +64,14 @@ impl ClientInner<ClientStateInit> {
         log::debug!("Updated gateway url {}", gateway.url());
 
         log::debug!("Move to gateway state");
+        self.state.status.set_state(ClientState::Gateway);
 
         ClientInner {
-            state: ClientStateGateway { gateway },
+            state: ClientStateGateway {
+                gateway,
+                config: self.state.config,
+                status: self.state.status,
+            },
         }
     }
 }
@@ -1,21 +1,42 @@
+mod config;
 mod connected;
 mod gateway;
 mod init;
+mod status;
 mod streaming;
 mod timeout;
 
 pub(super) use init::ClientStateInit;
 
+use status::StatusReporter;
+
+use crate::backoff::Backoff;
+
+pub use config::ClientConfig;
 pub use connected::WaitHelloError;
 pub use gateway::ConnectGatewayError;
 pub use init::RunError;
-pub use streaming::{EventStream, EventStreamError, EventStreamErrorKind};
+pub use status::{ClientState, ClientStatus};
+pub use streaming::{
+    EventBufferLimits, EventStream, EventStreamError, EventStreamErrorKind, EventStreamItem, Notice,
+};
+
+pub(crate) const CONNECT_GATEWAY_MAX_ATTEMPTS: usize = 2;
+
+pub(crate) const HELLO_TIMEOUT: u64 = 6;
 
 pub(crate) const PONG_TIMEOUT: u64 = 6;
 
 pub(crate) const STREAMING_STATE_PING_INTERVAL: u64 = 30;
 pub(crate) const STREAMING_STATE_PONG_TIMEOUT_MAX_COUNT: usize = 2;
 
+pub(crate) const RESUME_ACK_TIMEOUT: u64 = 10;
+
+pub(crate) const EVENT_BUFFER_MAX_SIZE: usize = 256;
+pub(crate) const EVENT_BUFFER_GAP_TIMEOUT: u64 = 10;
+
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 32;
+
 pub(crate) const TIMEOUT_STATE_SEND_PING_INTERVAL_START: u64 = 2;
 pub(crate) const TIMEOUT_STATE_SEND_PING_INTERVAL_MAX: u64 = PONG_TIMEOUT;
 
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use tokio::{sync::watch, time::Instant};
+
+/// Stage of the websocket client state machine
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ClientState {
+    /// not started yet
+    #[default]
+    Init,
+    /// connecting to gateway
+    Gateway,
+    /// connected, waiting hello message
+    Connected,
+    /// receiving events
+    Streaming,
+    /// pong timeout, trying to recover or reconnect
+    Timeout,
+    /// event stream ended with error or dropped, client is stopped
+    Stopped,
+}
+
+/// Status snapshot of the websocket client
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientStatus {
+    /// current stage
+    pub state: ClientState,
+    /// current session id, empty before hello received or after session reset
+    pub session_id: String,
+    /// sn of last event sent to event stream
+    pub sn: u64,
+    /// when last pong message received
+    pub last_pong: Option<Instant>,
+    /// how many times the client reconnected to gateway
+    pub reconnect_count: usize,
+}
+
+impl ClientStatus {
+    /// if events can be received now
+    pub fn is_streaming(&self) -> bool {
+        self.state == ClientState::Streaming
+    }
+}
+
+/// Writing side of client status, shared by all states and background tasks of a client
+#[derive(Debug, Clone)]
+pub(crate) struct StatusReporter {
+    tx: Arc<watch::Sender<ClientStatus>>,
+}
+
+impl Default for StatusReporter {
+    fn default() -> Self {
+        Self {
+            tx: Arc::new(watch::channel(ClientStatus::default()).0),
+        }
+    }
+}
+
+impl StatusReporter {
+    pub fn subscribe(&self) -> watch::Receiver<ClientStatus> {
+        self.tx.subscribe()
+    }
+
+    /// Modify status, watchers are only notified when it really changed
+    pub fn update<F: FnOnce(&mut ClientStatus)>(&self, f: F) {
+        self.tx.send_if_modified(|status| {
+            let old = status.clone();
+            f(status);
+            *status != old
+        });
+    }
+
+    pub fn set_state(&self, state: ClientState) {
+        self.update(|status| status.state = state);
+    }
+}
@@ -1,14 +1,43 @@
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashSet},
+    time::Duration,
 };
 
-use crate::ws::event::EventData;
+use tokio::time::Instant;
+
+use crate::ws::{
+    client::inner::{EVENT_BUFFER_GAP_TIMEOUT, EVENT_BUFFER_MAX_SIZE},
+    event::EventData,
+};
+
+/// Limits of out-of-order events buffer.
+///
+/// When a missing sn does not show up in `gap_timeout`, or more than `max_size` events are
+/// waiting for it, the missing sn is skipped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EventBufferLimits {
+    /// max count of buffered events
+    pub max_size: usize,
+    /// max time to wait a missing sn
+    pub gap_timeout: Duration,
+}
+
+impl Default for EventBufferLimits {
+    fn default() -> Self {
+        Self {
+            max_size: EVENT_BUFFER_MAX_SIZE,
+            gap_timeout: Duration::from_secs(EVENT_BUFFER_GAP_TIMEOUT),
+        }
+    }
+}
 
 #[derive(Debug, Default)]
 pub(crate) struct EventBuffer {
     exist: HashSet<u64>,
     buffer: BinaryHeap<Reverse<EventData>>,
+    limits: EventBufferLimits,
+    gap_since: Option<Instant>,
 }
 
 #[derive(Debug)]
@@ -31,6 +60,13 @@ impl Iterator for EventsCanBeSend<'_> {
 }
 
 impl EventBuffer {
+    pub fn new(limits: EventBufferLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
     pub fn put(&mut self, sn: u64, item: EventData) {
         if item.sn <= sn || self.exist.contains(&item.sn) {
             log::trace!("Duplicated event {} received, drop it", item.sn);
@@ -50,7 +86,86 @@ impl EventBuffer {
         Some(item.0)
     }
 
-    pub fn events_can_be_sent(&mut self, sn: u64) -> EventsCanBeSend {
+    pub fn events_can_be_sent(&mut self, sn: u64) -> EventsCanBeSend<'_> {
         EventsCanBeSend { sn, buffer: self }
     }
+
+    /// Update start time of current gap, call it after events are sent.
+    ///
+    /// `progressed` means some events were just sent, so the remaining gap is a new one.
+    pub fn refresh_gap(&mut self, progressed: bool) {
+        if self.buffer.is_empty() {
+            self.gap_since = None;
+        } else if progressed || self.gap_since.is_none() {
+            self.gap_since = Some(Instant::now());
+        }
+    }
+
+    /// When current gap should be skipped if the missing sn still does not show up
+    pub fn gap_deadline(&self) -> Option<Instant> {
+        self.gap_since.map(|since| since + self.limits.gap_timeout)
+    }
+
+    /// Missing sn range(inclusive) which should be skipped because buffer limits exceeded
+    pub fn gap_to_skip(&self, sn: u64) -> Option<(u64, u64)> {
+        let head = self.peek()?.sn;
+
+        let exceeded = self.buffer.len() > self.limits.max_size
+            || self
+                .gap_deadline()
+                .is_some_and(|deadline| deadline <= Instant::now());
+
+        (exceeded && head > sn + 1).then_some((sn + 1, head - 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ws::Event;
+
+    fn event(sn: u64) -> EventData {
+        EventData {
+            sn,
+            event: Event::Unknown(serde_json::Value::Null),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_event_buffer_gap_timeout() {
+        let mut buffer = EventBuffer::new(EventBufferLimits {
+            max_size: 10,
+            gap_timeout: Duration::from_secs(5),
+        });
+
+        buffer.put(1, event(4));
+        buffer.put(1, event(3));
+        buffer.refresh_gap(false);
+
+        assert_eq!(buffer.events_can_be_sent(1).count(), 0);
+        assert_eq!(buffer.gap_to_skip(1), None);
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(buffer.gap_to_skip(1), Some((2, 2)));
+
+        let sent: Vec<_> = buffer.events_can_be_sent(2).map(|e| e.sn).collect();
+        assert_eq!(sent, vec![3, 4]);
+
+        buffer.refresh_gap(true);
+        assert_eq!(buffer.gap_deadline(), None);
+    }
+
+    #[test]
+    fn test_event_buffer_max_size() {
+        let mut buffer = EventBuffer::new(EventBufferLimits {
+            max_size: 2,
+            gap_timeout: Duration::from_secs(60),
+        });
+
+        for sn in 5..8 {
+            buffer.put(1, event(sn));
+        }
+
+        assert_eq!(buffer.gap_to_skip(1), Some((2, 4)));
+    }
 }
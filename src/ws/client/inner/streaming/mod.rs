@@ -5,11 +5,12 @@ mod state;
 mod stream;
 
 pub(crate) use buffer::EventBuffer;
+pub use buffer::EventBufferLimits;
 pub(crate) use sender::EventStreamSender;
-pub(crate) use state::ClientStateStreaming;
+pub(crate) use state::{ClientStateStreaming, PendingResume};
 pub(crate) use stream::error;
 
-pub use stream::{EventStream, EventStreamError, EventStreamErrorKind};
+pub use stream::{EventStream, EventStreamError, EventStreamErrorKind, EventStreamItem, Notice};
 
 // =====
 
@@ -1,13 +1,18 @@
-use tokio::sync::{mpsc, watch};
+use tokio::{
+    sync::{mpsc, watch},
+    time::Instant,
+};
 
-use super::{EventBuffer, EventStream, EventStreamError, EventStreamErrorKind};
+use super::{
+    EventBuffer, EventStream, EventStreamError, EventStreamErrorKind, EventStreamItem, Notice,
+};
 use crate::{
     api::types::GatewayResumeArguments,
-    ws::{
-        event::EventData,
-        message::{MessageStreamSinkError, Reconnect},
-        Event, Message,
+    ws::client::{
+        inner::{status::ClientState, StatusReporter},
+        ClientConfig,
     },
+    ws::{event::EventData, message::MessageStreamSinkError, Message},
 };
 
 #[derive(Debug)]
@@ -57,15 +62,19 @@ impl SnRecorder {
 
 #[derive(Debug)]
 pub(crate) struct EventStreamSender {
+    config: ClientConfig,
+    status: StatusReporter,
     buffer: EventBuffer,
-    event_tx: mpsc::Sender<Result<Event, EventStreamError>>,
+    event_tx: mpsc::Sender<Result<EventStreamItem, EventStreamError>>,
     recorder: SnRecorder,
 }
 
 impl Clone for EventStreamSender {
     fn clone(&self) -> Self {
         Self {
-            buffer: EventBuffer::default(),
+            config: self.config.clone(),
+            status: self.status.clone(),
+            buffer: EventBuffer::new(self.config.buffer_limits),
             event_tx: self.event_tx.clone(),
             recorder: self.recorder.clone(),
         }
@@ -73,12 +82,19 @@ impl Clone for EventStreamSender {
 }
 
 impl EventStreamSender {
-    pub fn new(resume: GatewayResumeArguments) -> (Self, EventStream) {
-        let (event_tx, event_rx) = tokio::sync::mpsc::channel(32);
+    pub fn new(
+        resume: GatewayResumeArguments,
+        config: ClientConfig,
+        status: StatusReporter,
+    ) -> (Self, EventStream) {
+        let (event_tx, event_rx) = tokio::sync::mpsc::channel(config.event_channel_capacity.get());
+        let status_rx = status.subscribe();
 
         (
             Self {
-                buffer: EventBuffer::default(),
+                buffer: EventBuffer::new(config.buffer_limits),
+                config,
+                status,
                 event_tx,
                 recorder: SnRecorder {
                     resume,
@@ -86,7 +102,7 @@ impl EventStreamSender {
                     sn_notifier: None,
                 },
             },
-            EventStream { rx: event_rx },
+            EventStream::new(event_rx, Some(status_rx)),
         )
     }
 
@@ -108,6 +124,18 @@ impl EventStreamSender {
         &self.recorder.resume
     }
 
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    pub fn status(&self) -> &StatusReporter {
+        &self.status
+    }
+
+    pub fn gap_deadline(&self) -> Option<Instant> {
+        self.buffer.gap_deadline()
+    }
+
     pub fn sn(&self) -> u64 {
         self.recorder.resume.sn
     }
@@ -120,25 +148,57 @@ impl EventStreamSender {
         self.recorder.resume.ping()
     }
 
+    /// Send buffered events in order, skipping missing sn when buffer limits exceeded
     pub async fn flush(&mut self) -> bool {
-        for data in self.buffer.events_can_be_sent(self.sn()) {
-            if self.event_tx.send(Ok(data.event)).await.is_ok() {
-                log::trace!("Send event {} to event stream success", data.sn);
-            } else {
-                log::debug!(
-                    "Send event {} to event stream failed, means receive side dropped, stop",
-                    data.sn
-                );
-                // event receive side dropped, stop produce
-                return false;
+        loop {
+            let mut progressed = false;
+
+            for data in self.buffer.events_can_be_sent(self.recorder.resume.sn) {
+                if Self::send_item(
+                    &self.event_tx,
+                    &self.status,
+                    Ok(EventStreamItem::Event(data.event)),
+                )
+                .await
+                {
+                    log::trace!("Send event {} to event stream success", data.sn);
+                } else {
+                    log::debug!(
+                        "Send event {} to event stream failed, means receive side dropped, stop",
+                        data.sn
+                    );
+                    // event receive side dropped, stop produce
+                    return false;
+                }
+
+                self.status.update(|status| status.sn = data.sn);
+                if !self.recorder.update_sn(data.sn) {
+                    return false;
+                }
+
+                progressed = true;
             }
 
-            if !self.recorder.update_sn(data.sn) {
+            self.buffer.refresh_gap(progressed);
+
+            let (from, to) = match self.buffer.gap_to_skip(self.sn()) {
+                Some(gap) => gap,
+                None => return true,
+            };
+
+            log::warn!(
+                "Missing events sn {}..={} not received, skip them",
+                from,
+                to
+            );
+
+            if !self.send_notice(Notice::EventsSkipped { from, to }).await
+                || !self.recorder.update_sn(to)
+            {
                 return false;
             }
+            self.status.update(|status| status.sn = to);
         }
-
-        true
     }
 
     pub fn put(&mut self, event: EventData) {
@@ -150,23 +210,82 @@ impl EventStreamSender {
         self.flush().await
     }
 
+    async fn send_item(
+        event_tx: &mpsc::Sender<Result<EventStreamItem, EventStreamError>>,
+        status: &StatusReporter,
+        item: Result<EventStreamItem, EventStreamError>,
+    ) -> bool {
+        let alive = event_tx.send(item).await.is_ok();
+        if !alive {
+            status.set_state(ClientState::Stopped);
+        }
+        alive
+    }
+
     pub async fn send_err(&self, err: EventStreamErrorKind) -> bool {
-        self.event_tx
-            .send(Err(EventStreamError {
+        // event stream ends after an error
+        self.status.set_state(ClientState::Stopped);
+
+        Self::send_item(
+            &self.event_tx,
+            &self.status,
+            Err(EventStreamError {
                 resume: self.recorder.resume.clone(),
                 source: err,
-            }))
+            }),
+        )
+        .await
+    }
+
+    pub async fn send_notice(&self, notice: Notice) -> bool {
+        Self::send_item(
+            &self.event_tx,
+            &self.status,
+            Ok(EventStreamItem::Notice(notice)),
+        )
+        .await
+    }
+
+    /// Send a lifecycle notice if enabled in config
+    pub async fn send_lifecycle_notice(&self, notice: Notice) -> bool {
+        if !self.config.lifecycle_notices {
+            return true;
+        }
+        self.send_notice(notice).await
+    }
+
+    pub fn set_session_id(&mut self, session_id: String) {
+        self.status
+            .update(|status| status.session_id.clone_from(&session_id));
+        self.recorder.resume.session_id = session_id;
+    }
+
+    /// Hello received, start streaming
+    pub async fn on_connected(&mut self, session_id: String) -> bool {
+        log::trace!("Update status to streaming");
+        let sn = self.sn();
+        self.set_session_id(session_id.clone());
+        self.status.update(|status| {
+            status.state = ClientState::Streaming;
+            status.sn = sn;
+        });
+
+        self.send_lifecycle_notice(Notice::Connected { session_id })
             .await
-            .is_ok()
     }
 
-    pub async fn send_reconnect(&self, data: Reconnect) {
-        log::trace!("Send reconnect error to event stream");
-        self.send_err(EventStreamErrorKind::Reconnect {
-            code: data.code,
-            message: data.err,
-        })
-        .await;
+    /// Drop current session, sn and buffered events, then notify consumer about it
+    pub async fn reset_session(&mut self, notice: Notice) -> bool {
+        log::trace!("Reset session, sn and event buffer");
+        self.buffer = EventBuffer::new(self.config.buffer_limits);
+        self.recorder.resume = GatewayResumeArguments::default();
+        self.status.update(|status| {
+            status.session_id.clear();
+            status.sn = 0;
+        });
+
+        log::trace!("Send session reset notice to event stream");
+        self.send_notice(notice).await
     }
 
     pub async fn send_message_stream_broken(&self, err: MessageStreamSinkError) {
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use futures_util::{
     future,
@@ -7,21 +7,46 @@ use futures_util::{
 };
 use tokio::{sync::watch, task::JoinHandle, time::Instant};
 
-use super::{ping::PingWorker, EventStreamSender};
+use super::{ping::PingWorker, EventStreamErrorKind, EventStreamSender, Notice};
 use crate::{
-    api::types::GatewayURLInfo,
+    api::types::{GatewayResumeArguments, GatewayURLInfo},
     ws::{
-        client::inner::{
-            timeout::ClientStateTimeout, ClientInner, STREAMING_STATE_PONG_TIMEOUT_MAX_COUNT,
-        },
-        message::{Message, MessageStreamSinkError},
+        client::inner::{status::ClientState, timeout::ClientStateTimeout, ClientInner},
+        message::{Message, MessageStreamSinkError, ResumeACK},
     },
 };
 
+/// What streaming task should do after handled a message
+enum Next {
+    Continue,
+    Stop,
+    ResetSession(Notice),
+}
+
+/// A resume which is waiting for server ResumeACK
+#[derive(Debug)]
+pub(crate) struct PendingResume {
+    pub session_id: String,
+    pub deadline: Instant,
+}
+
+impl PendingResume {
+    /// Start waiting ACK if the connection is made with resume arguments
+    pub fn new(resume: Option<&GatewayResumeArguments>, timeout: Duration) -> Option<Self> {
+        let resume = resume.filter(|r| !r.session_id.is_empty())?;
+
+        Some(Self {
+            session_id: resume.session_id.clone(),
+            deadline: Instant::now() + timeout,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ClientStateStreaming<S> {
     pub gateway: GatewayURLInfo,
     pub sender: EventStreamSender,
+    pub pending_resume: Option<PendingResume>,
     pub sink: Option<SplitSink<S, Message>>,
     pub stream: SplitStream<S>,
 }
@@ -65,6 +90,7 @@ where
         pw_handler: JoinHandle<SplitSink<S, Message>>,
     ) -> ClientStateTimeout<S> {
         self.sender.remove_sn_notifier();
+        self.sender.status().set_state(ClientState::Timeout);
 
         log::trace!("Waiting ping worker to stop");
         let sink = pw_handler.await.unwrap();
@@ -72,39 +98,100 @@ where
         ClientStateTimeout::<S> {
             gateway: Some(self.gateway),
             sender: self.sender,
+            pending_resume: self.pending_resume,
             sink,
             stream: self.stream,
         }
     }
 
-    async fn on_message(&mut self, data: Option<Result<Message, MessageStreamSinkError>>) -> bool {
-        match data.unwrap() {
+    async fn on_resume_ack(&mut self, ack: ResumeACK) -> Next {
+        let pending = match self.pending_resume.take() {
+            Some(pending) => pending,
+            None => {
+                log::debug!("Received unexpected ResumeACK, ignore");
+                return Next::Continue;
+            }
+        };
+
+        if ack.session_id.is_empty() {
+            log::warn!("Received ResumeACK without session id, treat as resume failed");
+            return Next::ResetSession(Notice::ResumeFailed {
+                session_id: pending.session_id,
+            });
+        }
+
+        if ack.session_id != pending.session_id {
+            log::warn!(
+                "ResumeACK session id {} differs from resumed session {}, use the new one",
+                ack.session_id,
+                pending.session_id
+            );
+        }
+
+        log::info!("Session {} resumed", ack.session_id);
+
+        self.sender.set_session_id(ack.session_id.clone());
+
+        if self
+            .sender
+            .send_notice(Notice::Resumed {
+                session_id: ack.session_id,
+            })
+            .await
+        {
+            Next::Continue
+        } else {
+            Next::Stop
+        }
+    }
+
+    async fn on_message(&mut self, data: Option<Result<Message, MessageStreamSinkError>>) -> Next {
+        let data = match data {
+            Some(data) => data,
+            None => {
+                log::warn!("Connection closed by gateway");
+                self.sender
+                    .send_err(EventStreamErrorKind::MessageStreamClosed)
+                    .await;
+                log::debug!("Stop");
+                return Next::Stop;
+            }
+        };
+
+        match data {
             Ok(message) => {
                 log::trace!("Received new message type: {}", message.type_name());
 
                 match message {
                     Message::Event(data) => {
                         log::trace!("Received event sn = {}", data.sn);
-                        self.sender.send_event(data).await
-                    }
-                    Message::Reconnect(data) => {
-                        self.sender.send_reconnect(data.data).await;
-                        log::debug!("Stop");
-                        false
+                        if self.sender.send_event(data).await {
+                            Next::Continue
+                        } else {
+                            Next::Stop
+                        }
                     }
-                    Message::ResumeACK(_) => {
-                        // TODO: do we need update session id?
-                        true
+                    Message::Reconnect(data) => Next::ResetSession(Notice::SessionReset {
+                        code: data.data.code,
+                        message: data.data.err,
+                    }),
+                    Message::ResumeACK(ack) => self.on_resume_ack(ack.data).await,
+                    Message::Pong => {
+                        let now = Instant::now();
+                        self.sender
+                            .status()
+                            .update(|status| status.last_pong = Some(now));
+                        Next::Continue
                     }
                     // Ignore other message
-                    _ => true,
+                    _ => Next::Continue,
                 }
             }
             Err(err) => {
                 log::warn!("Find message stream broken when receive message: {}", err);
                 self.sender.send_message_stream_broken(err).await;
                 log::debug!("Stop");
-                false
+                Next::Stop
             }
         }
     }
@@ -129,9 +216,40 @@ where
                 future::pending().boxed()
             };
 
+            let resume_ack_clock = if let Some(ref pending) = self.pending_resume {
+                tokio::time::sleep_until(pending.deadline).boxed()
+            } else {
+                future::pending().boxed()
+            };
+
+            let event_gap_clock = if let Some(deadline) = self.sender.gap_deadline() {
+                tokio::time::sleep_until(deadline).boxed()
+            } else {
+                future::pending().boxed()
+            };
+
             tokio::select! {
                 biased;
 
+                // missing event still not received, skip it
+                _ = event_gap_clock => {
+                    if !self.sender.flush().await {
+                        break;
+                    }
+                }
+
+                // no ResumeACK in time
+                _ = resume_ack_clock => {
+                    let pending = self.pending_resume.take().unwrap();
+                    log::warn!("No ResumeACK received for session {} in time", pending.session_id);
+
+                    let timeout = self.into_timeout(pw_handler).await;
+                    timeout
+                        .reset_session(Notice::ResumeFailed { session_id: pending.session_id })
+                        .await;
+                    break;
+                }
+
                 // pong timeout
                 _ = pong_timeout_clock => {
                     pong_timeout_count += 1;
@@ -140,11 +258,16 @@ where
                     log::trace!("Reset pong timeout tick to inf");
                     pong_timeout_tick = None;
 
-                    if pong_timeout_count >= STREAMING_STATE_PONG_TIMEOUT_MAX_COUNT {
+                    if pong_timeout_count >= self.sender.config().pong_timeout_max_count {
                         log::warn!("Reached pong time out count limit, move to timeout state");
 
                         let client = ClientInner { state: self.into_timeout(pw_handler).await };
 
+                        if !client.state.sender.send_lifecycle_notice(Notice::TimedOut).await {
+                            log::debug!("Event stream receive side dropped, stop");
+                            break;
+                        }
+
                         log::debug!("Move to timeout state");
 
                         client.timeout_start();
@@ -170,8 +293,14 @@ where
                     pong_timeout_tick = None;
                     pong_timeout_count = 0;
 
-                    if !self.on_message(result).await {
-                        break;
+                    match self.on_message(result).await {
+                        Next::Continue => {}
+                        Next::Stop => break,
+                        Next::ResetSession(data) => {
+                            let timeout = self.into_timeout(pw_handler).await;
+                            timeout.reset_session(data).await;
+                            break;
+                        }
                     }
                 }
             }
@@ -1,13 +1,18 @@
-use std::task::Poll;
+use std::{fmt::Display, task::Poll};
 
+use enum_as_inner::EnumAsInner;
 use futures_util::Stream;
 use snafu::prelude::*;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 use super::super::ConnectGatewayError;
 use crate::{
     api::types::GatewayResumeArguments,
-    ws::{client::WaitHelloError, message::MessageStreamSinkError, Event},
+    ws::{
+        client::{ClientStatus, WaitHelloError},
+        message::{MessageStreamSinkError, ReconnectCode},
+        Event,
+    },
 };
 
 /// Error for event stream
@@ -32,15 +37,13 @@ pub enum EventStreamErrorKind {
         source: Box<dyn std::error::Error + Send>,
     },
 
-    /// received server reconnect message
-    #[snafu(display("received server reconnect request, code {code}, message: {message}"))]
-    Reconnect {
-        /// reconnect reason code
-        /// see: <https://developer.kaiheila.cn/doc/websocket#%E4%BF%A1%E4%BB%A4[5]%20RECONNECT>
-        code: i64,
-        /// reconnect reason message
-        message: String,
-    },
+    /// gateway closed the connection
+    #[snafu(display("connection closed by gateway"))]
+    MessageStreamClosed,
+
+    /// websocket client stopped without reporting an error, usually because it panicked
+    #[snafu(display("websocket client stopped unexpectedly"))]
+    ClientStopped,
 
     /// reconnect to websocket gateway failed
     #[snafu(display("(re)connect ws gateway failed: {source}"))]
@@ -49,6 +52,13 @@ pub enum EventStreamErrorKind {
         source: ConnectGatewayError,
     },
 
+    /// webhook http server stopped with error
+    #[snafu(display("webhook server failed: {source}"))]
+    WebhookServerFailed {
+        /// source error
+        source: hyper::Error,
+    },
+
     /// reconnect to websocket gateway failed
     #[snafu(display("(re)wait hello from ws gateway failed: {source}"))]
     ReWaitHelloFailed {
@@ -57,19 +67,151 @@ pub enum EventStreamErrorKind {
     },
 }
 
+impl EventStreamError {
+    /// if the session can be resumed using [`resume`](Self::resume),
+    /// otherwise a fresh session should be started
+    pub fn can_resume(&self) -> bool {
+        if self.resume.session_id.is_empty() {
+            return false;
+        }
+
+        match &self.source {
+            EventStreamErrorKind::ReWaitHelloFailed { source } => source.can_resume(),
+            _ => true,
+        }
+    }
+}
+
+/// Notice about event stream lifecycle, sent along with events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notice {
+    /// server asked to reconnect, last session and its sn are dropped and a new session
+    /// is started, events not received yet may be lost
+    SessionReset {
+        /// reconnect reason code
+        code: ReconnectCode,
+        /// reconnect reason message
+        message: String,
+    },
+    /// events in sn range `from..=to` were not received within buffer limits and skipped,
+    /// they can be back-filled via message list apis if needed
+    EventsSkipped {
+        /// first skipped sn
+        from: u64,
+        /// last skipped sn
+        to: u64,
+    },
+    /// server acknowledged the resume, events after last sn will be received
+    Resumed {
+        /// resumed session id
+        session_id: String,
+    },
+    /// no valid ResumeACK received in time, last session and its sn are dropped and a new
+    /// session is started, events not received yet may be lost
+    ResumeFailed {
+        /// session id we tried to resume
+        session_id: String,
+    },
+    /// hello received from gateway, only sent when lifecycle notices enabled
+    Connected {
+        /// session id of the connection
+        session_id: String,
+    },
+    /// pong timeout, events stop until connection recovered, only sent when lifecycle
+    /// notices enabled
+    TimedOut,
+    /// reconnecting to gateway, only sent when lifecycle notices enabled
+    Reconnecting {
+        /// how many times the client reconnected, this one included
+        count: usize,
+    },
+}
+
+impl Display for Notice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SessionReset { code, message } => write!(
+                f,
+                "session reset by server, code {code}, message: {message}, events may be lost"
+            ),
+            Self::EventsSkipped { from, to } => write!(f, "events skipped: sn {from}..{to}"),
+            Self::Resumed { session_id } => write!(f, "session {session_id} resumed"),
+            Self::ResumeFailed { session_id } => write!(
+                f,
+                "resume session {session_id} failed, new session started, events may be lost"
+            ),
+            Self::Connected { session_id } => write!(f, "connected with session {session_id}"),
+            Self::TimedOut => write!(f, "pong timeout, waiting connection recovery"),
+            Self::Reconnecting { count } => write!(f, "reconnecting to gateway, count {count}"),
+        }
+    }
+}
+
+/// Item of event stream
+#[derive(Debug, Clone, PartialEq, Eq, EnumAsInner)]
+pub enum EventStreamItem {
+    /// kaiheila event
+    Event(Event),
+    /// lifecycle notice
+    Notice(Notice),
+}
+
 /// Kaiheila websocket event stream
 #[derive(Debug)]
 pub struct EventStream {
-    pub(crate) rx: mpsc::Receiver<Result<Event, EventStreamError>>,
+    rx: mpsc::Receiver<Result<EventStreamItem, EventStreamError>>,
+    status: Option<watch::Receiver<ClientStatus>>,
+    failed: bool,
+}
+
+impl EventStream {
+    /// `status` is given only for websocket client, whose stream always ends with an error
+    pub(crate) fn new(
+        rx: mpsc::Receiver<Result<EventStreamItem, EventStreamError>>,
+        status: Option<watch::Receiver<ClientStatus>>,
+    ) -> Self {
+        Self {
+            rx,
+            status,
+            failed: false,
+        }
+    }
+
+    /// Watch status of the websocket client producing this stream.
+    ///
+    /// `None` if the stream is not produced by websocket client, like webhook or replay.
+    pub fn status(&self) -> Option<watch::Receiver<ClientStatus>> {
+        self.status.clone()
+    }
 }
 
 impl Stream for EventStream {
-    type Item = Result<Event, EventStreamError>;
+    type Item = Result<EventStreamItem, EventStreamError>;
 
     fn poll_next(
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        self.rx.poll_recv(cx)
+        let mut item = futures_util::ready!(self.rx.poll_recv(cx));
+
+        if item.is_none() && !self.failed {
+            if let Some(ref status) = self.status {
+                // client task ended without an error, report it so the stream can be reopened
+                let status = status.borrow();
+                item = Some(Err(EventStreamError {
+                    resume: GatewayResumeArguments {
+                        sn: status.sn,
+                        session_id: status.session_id.clone(),
+                    },
+                    source: EventStreamErrorKind::ClientStopped,
+                }));
+            }
+        }
+
+        if matches!(item, Some(Err(_))) {
+            self.failed = true;
+        }
+
+        Poll::Ready(item)
     }
 }
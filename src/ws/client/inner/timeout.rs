@@ -1,4 +1,4 @@
-use std::{fmt::Debug, time::Duration};
+use std::fmt::Debug;
 
 use futures_util::{
     stream::{SplitSink, SplitStream},
@@ -9,24 +9,20 @@ use tokio::time::Instant;
 
 use super::{
     connected::ClientStateConnected,
+    status::ClientState,
     streaming::error,
-    streaming::{self, ClientStateStreaming, EventStreamSender},
+    streaming::{self, ClientStateStreaming, EventStreamSender, Notice, PendingResume},
     ClientInner, ClientStateInit,
 };
 use crate::{
     api::types::GatewayURLInfo,
-    ws::{
-        client::inner::{
-            PONG_TIMEOUT, TIMEOUT_STATE_SEND_PING_INTERVAL_MAX,
-            TIMEOUT_STATE_SEND_PING_INTERVAL_START,
-        },
-        message::{Message, MessageStreamSinkError},
-    },
+    ws::message::{Message, MessageStreamSinkError},
 };
 
 pub(crate) struct ClientStateTimeout<S> {
     pub gateway: Option<GatewayURLInfo>,
     pub sender: EventStreamSender,
+    pub pending_resume: Option<PendingResume>,
     pub sink: SplitSink<S, Message>,
     pub stream: SplitStream<S>,
 }
@@ -44,15 +40,35 @@ where
         ClientStateStreaming::<S> {
             gateway: self.gateway.unwrap(),
             sender: self.sender,
+            pending_resume: self.pending_resume,
             sink: Some(self.sink),
             stream: self.stream,
         }
     }
 
     async fn reconnect(&mut self) -> Option<ClientStateConnected> {
+        let mut count = 0;
+        self.sender.status().update(|status| {
+            status.reconnect_count += 1;
+            count = status.reconnect_count;
+        });
+
+        if !self
+            .sender
+            .send_lifecycle_notice(Notice::Reconnecting { count })
+            .await
+        {
+            log::debug!("Event stream receive side dropped, stop");
+            return None;
+        }
+
+        // session id is empty after session reset, connect without resume then
+        let resume = Some(self.sender.resume().clone()).filter(|r| !r.session_id.is_empty());
         let client = ClientInner {
             state: ClientStateInit {
-                resume: Some(self.sender.resume().clone()),
+                resume,
+                config: self.sender.config().clone(),
+                status: self.sender.status().clone(),
             },
         };
 
@@ -77,10 +93,21 @@ where
 
                 match message {
                     Message::Reconnect(data) => {
-                        self.sender.send_reconnect(data.data).await;
-                        log::debug!("Stop");
+                        self.reset_session(Notice::SessionReset {
+                            code: data.data.code,
+                            message: data.data.err,
+                        })
+                        .await;
                     }
                     _ => {
+                        let now = Instant::now();
+                        self.sender.status().update(|status| {
+                            if matches!(message, Message::Pong) {
+                                status.last_pong = Some(now);
+                            }
+                            status.state = ClientState::Streaming;
+                        });
+
                         if let Ok(data) = message.into_event() {
                             self.sender.put(data);
                         }
@@ -103,14 +130,30 @@ where
         };
     }
 
+    /// Drop current session and connect to gateway as a new session, `notice` tells consumer why
+    pub async fn reset_session(mut self, notice: Notice) {
+        log::warn!("Start a new session: {}", notice);
+
+        if !self.sender.reset_session(notice).await {
+            log::debug!("Event stream receive side dropped, stop");
+            return;
+        }
+
+        if let Some(connected) = self.reconnect().await {
+            log::debug!("Reconnect success");
+            let client = ClientInner { state: connected };
+            client.re_wait_hello(self.sender).await;
+        }
+    }
+
     pub async fn waiting(mut self) {
         log::debug!("Timeout background task start");
 
-        let pong_timeout_clock = tokio::time::sleep(Duration::from_secs(PONG_TIMEOUT));
+        let pong_timeout_clock = tokio::time::sleep(self.sender.config().pong_timeout);
         tokio::pin!(pong_timeout_clock);
 
-        let mut send_ping_delay = 0;
-        let mut send_ping_tick = Instant::now();
+        let mut send_ping_delays = self.sender.config().timeout_ping_backoff.delays();
+        let mut send_ping_tick = Some(Instant::now());
 
         loop {
             tokio::select! {
@@ -128,12 +171,12 @@ where
                     return;
                 }
 
-                _ = tokio::time::sleep_until(send_ping_tick) => {
+                _ = tokio::time::sleep_until(send_ping_tick.unwrap()), if send_ping_tick.is_some() => {
                     log::trace!("Send ping with sn {}", self.sender.sn());
 
                     if let Err(err) = self
                     .sink
-                    .feed(self.sender.ping())
+                    .send(self.sender.ping())
                     .await
                     .context(streaming::error::MessageStream)
                     {
@@ -144,16 +187,22 @@ where
                         return;
                     }
 
-                    send_ping_delay *= 2;
-                    send_ping_delay = send_ping_delay.clamp(TIMEOUT_STATE_SEND_PING_INTERVAL_START, TIMEOUT_STATE_SEND_PING_INTERVAL_MAX);
-
-                    log::trace!("Next ping in {} seconds", send_ping_delay);
-
-                    send_ping_tick = Instant::now() + Duration::from_secs(send_ping_delay);
+                    send_ping_tick = send_ping_delays.next().map(|delay| {
+                        log::trace!("Next ping in {:?}", delay);
+                        Instant::now() + delay
+                    });
                 }
 
                 result = self.stream.next() => {
-                    self.on_message(result.unwrap()).await;
+                    match result {
+                        Some(message) => self.on_message(message).await,
+                        None => {
+                            log::warn!("Connection closed by gateway, stop");
+                            self.sender
+                                .send_err(streaming::EventStreamErrorKind::MessageStreamClosed)
+                                .await;
+                        }
+                    }
                     return;
                 }
             }
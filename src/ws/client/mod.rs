@@ -1,10 +1,12 @@
 //! Kaiheila websocket client
 
+#[cfg(test)]
+pub(crate) mod fake;
 mod inner;
 
 pub use inner::{
-    ConnectGatewayError, EventStream, EventStreamError, EventStreamErrorKind, RunError,
-    WaitHelloError,
+    ClientConfig, ClientState, ClientStatus, ConnectGatewayError, EventBufferLimits, EventStream,
+    EventStreamError, EventStreamErrorKind, EventStreamItem, Notice, RunError, WaitHelloError,
 };
 
 use tokio_tungstenite as websocket;
@@ -20,6 +22,9 @@ pub(crate) type WebsocketClient =
 #[derive(Debug)]
 pub struct Client {
     inner: ClientInner<ClientStateInit>,
+    /// settings given by their own setters, kept when the whole config is replaced
+    buffer_limits: Option<EventBufferLimits>,
+    lifecycle_notices: Option<bool>,
 }
 
 impl Default for Client {
@@ -33,18 +38,55 @@ impl Client {
     pub fn new() -> Self {
         Self {
             inner: ClientInner {
-                state: ClientStateInit { resume: None },
+                state: ClientStateInit {
+                    resume: None,
+                    config: ClientConfig::default(),
+                    status: Default::default(),
+                },
             },
+            buffer_limits: None,
+            lifecycle_notices: None,
         }
     }
 
     /// Create a client and resume from last session
     pub fn resume(args: GatewayResumeArguments) -> Self {
-        Self {
-            inner: ClientInner {
-                state: ClientStateInit { resume: Some(args) },
-            },
+        let mut client = Self::new();
+        client.inner.state.resume.replace(args);
+        client
+    }
+
+    /// Set limits of the buffer which reorders out-of-order events.
+    ///
+    /// Takes precedence over [ClientConfig::buffer_limits], no matter which is set first.
+    pub fn with_buffer_limits(mut self, limits: EventBufferLimits) -> Self {
+        self.buffer_limits.replace(limits);
+        self.inner.state.config.buffer_limits = limits;
+        self
+    }
+
+    /// Send lifecycle notices to event stream or not.
+    ///
+    /// Takes precedence over [ClientConfig::lifecycle_notices], no matter which is set first.
+    pub fn with_lifecycle_notices(mut self, enabled: bool) -> Self {
+        self.lifecycle_notices.replace(enabled);
+        self.inner.state.config.lifecycle_notices = enabled;
+        self
+    }
+
+    /// Set timing and capacity settings of the client.
+    ///
+    /// Settings given by [with_buffer_limits](Self::with_buffer_limits) and
+    /// [with_lifecycle_notices](Self::with_lifecycle_notices) are kept.
+    pub fn with_config(mut self, config: ClientConfig) -> Self {
+        self.inner.state.config = config;
+        if let Some(limits) = self.buffer_limits {
+            self.inner.state.config.buffer_limits = limits;
         }
+        if let Some(enabled) = self.lifecycle_notices {
+            self.inner.state.config.lifecycle_notices = enabled;
+        }
+        self
     }
 
     /// start running the client in given gateway, returning a stream for kaiheila event
@@ -52,3 +94,358 @@ impl Client {
         self.inner.run(gateway).await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
+
+    use futures_util::StreamExt;
+    use tokio::sync::{mpsc, watch};
+
+    use super::{fake::FakeGateway, *};
+    use crate::ws::message::ReconnectCode;
+
+    fn event_sn(item: EventStreamItem) -> u64 {
+        item.into_event().unwrap().as_unknown().unwrap()["sn"]
+            .as_u64()
+            .unwrap()
+    }
+
+    /// Resume session `old-session` at sn 10, the gateway answers `ack` (if any) right after hello,
+    /// then accepts a second connection for a new session if the resume fails
+    async fn resume_with_ack(
+        ack: Option<serde_json::Value>,
+    ) -> (
+        EventStream,
+        mpsc::UnboundedReceiver<HashMap<String, String>>,
+    ) {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        let (query_tx, query_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut conn = gateway.accept().await;
+            query_tx.send(conn.query.clone()).unwrap();
+            conn.hello("old-session").await;
+            if let Some(ack) = ack {
+                conn.send(ack).await;
+            }
+            conn.event(11).await;
+            tokio::spawn(conn.pong_forever());
+
+            let mut conn = gateway.accept().await;
+            query_tx.send(conn.query.clone()).unwrap();
+            conn.hello("new-session").await;
+            conn.event(1).await;
+            conn.pong_forever().await;
+        });
+
+        let config = ClientConfig {
+            resume_ack_timeout: Duration::from_millis(100),
+            ..ClientConfig::default()
+        };
+        let stream = Client::resume(GatewayResumeArguments {
+            sn: 10,
+            session_id: "old-session".to_string(),
+        })
+        .with_config(config)
+        .run(url)
+        .await
+        .unwrap();
+
+        (stream, query_rx)
+    }
+
+    async fn assert_resume_failed(
+        mut stream: EventStream,
+        mut query_rx: mpsc::UnboundedReceiver<HashMap<String, String>>,
+    ) {
+        let query = query_rx.recv().await.unwrap();
+        assert_eq!(query["resume"], "1");
+
+        let mut item = stream.next().await.unwrap().unwrap();
+        // the event may arrive before the resume is given up
+        if item.as_event().is_some() {
+            assert_eq!(event_sn(item), 11);
+            item = stream.next().await.unwrap().unwrap();
+        }
+        assert_eq!(
+            item.into_notice().unwrap(),
+            Notice::ResumeFailed {
+                session_id: "old-session".to_string()
+            }
+        );
+
+        let query = query_rx.recv().await.unwrap();
+        assert!(!query.contains_key("resume"));
+        assert!(!query.contains_key("sn"));
+        assert!(!query.contains_key("session_id"));
+
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 1);
+        let status = stream.status().unwrap().borrow().clone();
+        assert_eq!(status.session_id, "new-session");
+    }
+
+    #[tokio::test]
+    async fn test_resume_ack() {
+        let (mut stream, mut query_rx) = resume_with_ack(Some(
+            serde_json::json!({ "s": 6, "d": { "session_id": "acked-session" } }),
+        ))
+        .await;
+
+        let query = query_rx.recv().await.unwrap();
+        assert_eq!(query["resume"], "1");
+        assert_eq!(query["sn"], "10");
+        assert_eq!(query["session_id"], "old-session");
+
+        let notice = stream.next().await.unwrap().unwrap().into_notice().unwrap();
+        assert_eq!(
+            notice,
+            Notice::Resumed {
+                session_id: "acked-session".to_string()
+            }
+        );
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 11);
+
+        let status = stream.status().unwrap().borrow().clone();
+        assert_eq!(status.session_id, "acked-session");
+        assert_eq!(status.sn, 11);
+    }
+
+    #[tokio::test]
+    async fn test_resume_ack_without_session_id() {
+        let (stream, query_rx) = resume_with_ack(Some(
+            serde_json::json!({ "s": 6, "d": { "session_id": "" } }),
+        ))
+        .await;
+        assert_resume_failed(stream, query_rx).await;
+
+        let (stream, query_rx) =
+            resume_with_ack(Some(serde_json::json!({ "s": 6, "d": {} }))).await;
+        assert_resume_failed(stream, query_rx).await;
+    }
+
+    #[tokio::test]
+    async fn test_resume_ack_timeout() {
+        let (stream, query_rx) = resume_with_ack(None).await;
+        assert_resume_failed(stream, query_rx).await;
+    }
+
+    #[tokio::test]
+    async fn test_hello_timeout_config() {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        // a gateway which never says hello
+        let conn = tokio::spawn(async move { gateway.accept().await });
+
+        let config = ClientConfig {
+            hello_timeout: Duration::from_millis(50),
+            ..ClientConfig::default()
+        };
+
+        let start = std::time::Instant::now();
+        let result = Client::new().with_config(config).run(url).await;
+
+        assert!(matches!(
+            result,
+            Err(RunError::WaitHelloFailed {
+                source: WaitHelloError::Timeout
+            })
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        drop(conn.await);
+    }
+
+    #[tokio::test]
+    async fn test_status_and_lifecycle_notice() {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        tokio::spawn(async move {
+            let mut conn = gateway.accept().await;
+            conn.hello("test-session").await;
+            conn.pong_forever().await;
+        });
+
+        let config = ClientConfig {
+            lifecycle_notices: true,
+            ..ClientConfig::default()
+        };
+
+        let mut stream = Client::new().with_config(config).run(url).await.unwrap();
+
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            item.into_notice().unwrap(),
+            Notice::Connected {
+                session_id: "test-session".to_string()
+            }
+        );
+
+        let mut status = stream.status().unwrap();
+        let status = status
+            .wait_for(|status| status.last_pong.is_some())
+            .await
+            .unwrap()
+            .clone();
+        assert_eq!(status.state, ClientState::Streaming);
+        assert_eq!(status.session_id, "test-session");
+        assert_eq!(status.reconnect_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_connection_closed_by_gateway() {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        tokio::spawn(async move {
+            let mut conn = gateway.accept().await;
+            conn.hello("test-session").await;
+            conn.event(1).await;
+            conn.close().await;
+        });
+
+        let mut stream = Client::new().run(url).await.unwrap();
+
+        assert!(stream.next().await.unwrap().unwrap().as_event().is_some());
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            err.source,
+            EventStreamErrorKind::MessageStreamClosed
+        ));
+        assert_eq!(err.resume.session_id, "test-session");
+        assert_eq!(err.resume.sn, 1);
+        assert!(err.can_resume());
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_reports_stopped_client() {
+        let (tx, rx) = mpsc::channel(1);
+        let (_status_tx, status_rx) = watch::channel(ClientStatus {
+            session_id: "test-session".to_string(),
+            sn: 3,
+            ..ClientStatus::default()
+        });
+
+        // client task ended without sending an error
+        drop(tx);
+
+        let mut stream = EventStream::new(rx, Some(status_rx));
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err.source, EventStreamErrorKind::ClientStopped));
+        assert_eq!(err.resume.sn, 3);
+        assert!(err.can_resume());
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_message_resets_session() {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        let (query_tx, mut query_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut conn = gateway.accept().await;
+            conn.hello("old-session").await;
+            conn.event(1).await;
+            conn.event(2).await;
+            // buffered waiting for sn 3, should be dropped by the reset
+            conn.event(4).await;
+            conn.send(serde_json::json!({ "s": 5, "d": { "code": 40108, "err": "invalid sn" } }))
+                .await;
+
+            let mut conn = gateway.accept().await;
+            query_tx.send(conn.query.clone()).unwrap();
+            conn.hello("new-session").await;
+            conn.event(1).await;
+            conn.pong_forever().await;
+        });
+
+        let mut stream = Client::new().run(url).await.unwrap();
+
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 1);
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 2);
+
+        let notice = stream.next().await.unwrap().unwrap().into_notice().unwrap();
+        assert_eq!(
+            notice,
+            Notice::SessionReset {
+                code: ReconnectCode::InvalidSn,
+                message: "invalid sn".to_string()
+            }
+        );
+
+        // new session is not a resume
+        let query = query_rx.recv().await.unwrap();
+        assert!(!query.contains_key("resume"));
+        assert!(!query.contains_key("sn"));
+        assert!(!query.contains_key("session_id"));
+
+        // sn starts over in the new session
+        assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), 1);
+
+        let status = stream.status().unwrap().borrow().clone();
+        assert_eq!(status.session_id, "new-session");
+        assert_eq!(status.sn, 1);
+        assert_eq!(status.reconnect_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_min_event_channel_capacity() {
+        let mut gateway = FakeGateway::start().await;
+        let url = gateway.gateway();
+
+        tokio::spawn(async move {
+            let mut conn = gateway.accept().await;
+            conn.hello("test-session").await;
+            for sn in 1..=3 {
+                conn.event(sn).await;
+            }
+            conn.pong_forever().await;
+        });
+
+        let config = ClientConfig {
+            event_channel_capacity: NonZeroUsize::MIN,
+            ..ClientConfig::default()
+        };
+        let mut stream = Client::new().with_config(config).run(url).await.unwrap();
+
+        for sn in 1..=3 {
+            assert_eq!(event_sn(stream.next().await.unwrap().unwrap()), sn);
+        }
+    }
+
+    #[test]
+    fn test_setters_kept_by_with_config() {
+        let limits = EventBufferLimits {
+            max_size: 1,
+            gap_timeout: Duration::from_millis(1),
+        };
+        let config = ClientConfig {
+            hello_timeout: Duration::from_millis(1),
+            ..ClientConfig::default()
+        };
+
+        let before = Client::new()
+            .with_buffer_limits(limits)
+            .with_lifecycle_notices(true)
+            .with_config(config.clone());
+        let after = Client::new()
+            .with_config(config)
+            .with_buffer_limits(limits)
+            .with_lifecycle_notices(true);
+
+        for client in [before, after] {
+            let config = &client.inner.state.config;
+            assert_eq!(config.buffer_limits, limits);
+            assert!(config.lifecycle_notices);
+            assert_eq!(config.hello_timeout, Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::ChannelType;
+use crate::api::types::User;
+
+/// Message type of a message event
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum MessageType {
+    /// plain text message
+    Text,
+    /// image message, content is the image url
+    Image,
+    /// video message, content is the video url
+    Video,
+    /// file message, content is the file url
+    File,
+    /// audio message, content is the audio url
+    Audio,
+    /// KMarkdown message
+    KMarkdown,
+    /// card message, content is the card json
+    Card,
+}
+
+/// Error when convert a number into [`MessageType`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMessageType(pub u8);
+
+impl Display for UnknownMessageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown message type {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMessageType {}
+
+impl TryFrom<u8> for MessageType {
+    type Error = UnknownMessageType;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Text),
+            2 => Ok(Self::Image),
+            3 => Ok(Self::Video),
+            4 => Ok(Self::File),
+            8 => Ok(Self::Audio),
+            9 => Ok(Self::KMarkdown),
+            10 => Ok(Self::Card),
+            _ => Err(UnknownMessageType(value)),
+        }
+    }
+}
+
+impl From<MessageType> for u8 {
+    fn from(t: MessageType) -> Self {
+        match t {
+            MessageType::Text => 1,
+            MessageType::Image => 2,
+            MessageType::Video => 3,
+            MessageType::File => 4,
+            MessageType::Audio => 8,
+            MessageType::KMarkdown => 9,
+            MessageType::Card => 10,
+        }
+    }
+}
+
+/// Event for a message sent by user or bot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageEvent {
+    /// where the message is sent
+    pub channel_type: ChannelType,
+    /// message type
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    /// channel id for group message, user id for person message
+    pub target_id: String,
+    /// sender user id
+    pub author_id: String,
+    /// message content
+    pub content: String,
+    /// message id
+    pub msg_id: String,
+    /// send time, unix timestamp in milliseconds
+    pub msg_timestamp: u64,
+    /// random string given by sender
+    #[serde(default)]
+    pub nonce: String,
+    /// type specified extra data
+    pub extra: MessageExtra,
+}
+
+impl MessageEvent {
+    /// the guild id where message is sent, `None` for private message
+    pub fn guild_id(&self) -> Option<&str> {
+        self.extra.guild_id.as_deref()
+    }
+
+    /// check if this message is sent by a bot
+    pub fn is_from_bot(&self) -> bool {
+        self.extra.author.bot
+    }
+}
+
+/// Extra data of message event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageExtra {
+    /// message type, same as [`MessageEvent::message_type`]
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    /// guild id, only exists in group message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<String>,
+    /// channel name, only exists in group message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_name: Option<String>,
+    /// private chat code, only exists in person message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// mentioned user ids
+    #[serde(default)]
+    pub mention: Vec<String>,
+    /// if mentioned all users
+    #[serde(default)]
+    pub mention_all: bool,
+    /// mentioned role ids
+    #[serde(default)]
+    pub mention_roles: Vec<u64>,
+    /// if mentioned online users
+    #[serde(default)]
+    pub mention_here: bool,
+    /// message sender
+    pub author: User,
+    /// attachment of image/video/file/audio message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Attachment>,
+    /// parsed KMarkdown info of KMarkdown message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kmarkdown: Option<KMarkdownInfo>,
+}
+
+/// Attachment of a media message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// attachment type, like `image`, `video`, `file`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// url of the attachment
+    pub url: String,
+    /// file name
+    #[serde(default)]
+    pub name: String,
+    /// file size in bytes
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// Parsed info of a KMarkdown message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KMarkdownInfo {
+    /// content with markdown syntax removed
+    pub raw_content: String,
+}
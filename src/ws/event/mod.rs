@@ -0,0 +1,255 @@
+//! Kaiheila websocket events in [Event](super::message::Message::Event) message type.
+
+mod message;
+mod system;
+
+pub use message::{
+    Attachment, KMarkdownInfo, MessageEvent, MessageExtra, MessageType, UnknownMessageType,
+};
+pub use system::{
+    AddedBlockList, ButtonClick, DeletedBlockList, DeletedChannel, DeletedMessage,
+    DeletedPrivateMessage, Emoji, ExitedChannel, ExitedGuild, GuildMemberPresence, JoinedChannel,
+    JoinedGuild, PinnedMessage, PrivateReaction, Reaction, SelfGuild, SystemEvent, SystemEventKind,
+    UpdatedGuildMember, UpdatedMessage, UpdatedPrivateMessage, UserUpdated,
+};
+
+use enum_as_inner::EnumAsInner;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Event data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventData {
+    /// serial number
+    pub sn: u64,
+
+    /// event body
+    #[serde(rename = "d")]
+    pub event: Event,
+}
+
+impl PartialOrd for EventData {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EventData {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sn.cmp(&other.sn)
+    }
+}
+
+/// Where an event happened
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChannelType {
+    /// guild channel
+    Group,
+    /// private chat
+    Person,
+    /// broadcast
+    Broadcast,
+}
+
+const SYSTEM_EVENT_TYPE: u64 = 255;
+
+/// Kaiheila event
+#[derive(Debug, Clone, PartialEq, Eq, EnumAsInner)]
+pub enum Event {
+    /// message sent by user or bot
+    Message(Box<MessageEvent>),
+    /// system event
+    System(Box<SystemEvent>),
+    /// event which can't be recognized, keep the raw json
+    Unknown(serde_json::Value),
+}
+
+impl Event {
+    /// where the event happened
+    pub fn channel_type(&self) -> Option<ChannelType> {
+        match self {
+            Self::Message(m) => Some(m.channel_type),
+            Self::System(s) => Some(s.channel_type),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// target id of the event, channel id for message event, guild id for system event
+    pub fn target_id(&self) -> Option<&str> {
+        match self {
+            Self::Message(m) => Some(&m.target_id),
+            Self::System(s) => Some(&s.target_id),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// message(event) id
+    pub fn msg_id(&self) -> Option<&str> {
+        match self {
+            Self::Message(m) => Some(&m.msg_id),
+            Self::System(s) => Some(&s.msg_id),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    fn from_value(value: serde_json::Value) -> Self {
+        let t = value.get("type").and_then(serde_json::Value::as_u64);
+
+        let result = if t == Some(SYSTEM_EVENT_TYPE) {
+            serde_json::from_value(value.clone()).map(Self::System)
+        } else {
+            serde_json::from_value(value.clone()).map(Self::Message)
+        };
+
+        result.unwrap_or_else(|err| {
+            log::debug!("Can't recognize event, keep it as unknown: {}", err);
+            Self::Unknown(value)
+        })
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Message(m) => m.serialize(serializer),
+            Self::System(s) => {
+                let mut value = serde_json::to_value(s).map_err(serde::ser::Error::custom)?;
+                if let Some(obj) = value.as_object_mut() {
+                    obj.insert("type".to_string(), SYSTEM_EVENT_TYPE.into());
+                }
+                value.serialize(serializer)
+            }
+            Self::Unknown(v) => v.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_json::Value::deserialize(deserializer).map(Self::from_value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn author() -> serde_json::Value {
+        json!({
+            "id": "2862900000",
+            "username": "tz-un",
+            "identify_num": "5618",
+            "online": true,
+            "bot": false,
+            "avatar": "https://img.kaiheila.cn/avatars/2020-02/xxxx.jpg/icon",
+            "roles": [111],
+        })
+    }
+
+    #[test]
+    fn test_event_decode_text_message() {
+        let event: Event = serde_json::from_value(json!({
+            "channel_type": "GROUP",
+            "type": 1,
+            "target_id": "xxxxxx",
+            "author_id": "2862900000",
+            "content": "hello world",
+            "msg_id": "67637d4c-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+            "msg_timestamp": 1607680283000u64,
+            "nonce": "",
+            "extra": {
+                "type": 1,
+                "guild_id": "xxxxx",
+                "channel_name": "文字频道",
+                "mention": [],
+                "mention_all": false,
+                "mention_roles": [],
+                "mention_here": false,
+                "author": author(),
+            },
+        }))
+        .unwrap();
+
+        let message = event.into_message().unwrap();
+        assert_eq!(message.message_type, MessageType::Text);
+        assert_eq!(message.channel_type, ChannelType::Group);
+        assert_eq!(message.content, "hello world");
+        assert_eq!(message.guild_id(), Some("xxxxx"));
+        assert_eq!(message.extra.author.roles, vec![111]);
+    }
+
+    #[test]
+    fn test_event_decode_system_event() {
+        let event: Event = serde_json::from_value(json!({
+            "channel_type": "GROUP",
+            "type": 255,
+            "target_id": "xxxxx",
+            "author_id": "1",
+            "content": "[系统消息]",
+            "msg_id": "xxxxx",
+            "msg_timestamp": 1607680283000u64,
+            "nonce": "",
+            "extra": {
+                "type": "joined_guild",
+                "body": {
+                    "user_id": "2862900000",
+                    "joined_at": 1607680283000u64,
+                },
+            },
+        }))
+        .unwrap();
+
+        let system = event.into_system().unwrap();
+        assert_eq!(
+            system.extra,
+            SystemEventKind::JoinedGuild(JoinedGuild {
+                user_id: "2862900000".to_string(),
+                joined_at: 1607680283000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_event_decode_unknown() {
+        let value = json!({
+            "channel_type": "GROUP",
+            "type": 255,
+            "target_id": "xxxxx",
+            "author_id": "1",
+            "content": "",
+            "msg_id": "xxxxx",
+            "msg_timestamp": 1607680283000u64,
+            "extra": {
+                "type": "some_new_event",
+                "body": {},
+            },
+        });
+
+        let event: Event = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(event, Event::Unknown(value));
+    }
+
+    #[test]
+    fn test_event_encode_system_event_round_trip() {
+        let event = Event::System(Box::new(SystemEvent {
+            channel_type: ChannelType::Person,
+            target_id: "xxxxx".to_string(),
+            author_id: "1".to_string(),
+            content: String::new(),
+            msg_id: "xxxxx".to_string(),
+            msg_timestamp: 1607680283000,
+            nonce: String::new(),
+            extra: SystemEventKind::SelfJoinedGuild(SelfGuild {
+                guild_id: "xxxxx".to_string(),
+            }),
+        }));
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], 255);
+
+        let decoded: Event = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, event);
+    }
+}
@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+
+use super::ChannelType;
+use crate::api::types::{Channel, Guild, Role, User};
+
+/// Event generated by kaiheila system, like guild/channel/member changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemEvent {
+    /// where the event happened
+    pub channel_type: ChannelType,
+    /// guild id for group event, user id for person event
+    pub target_id: String,
+    /// always `"1"` for system
+    pub author_id: String,
+    /// event content, usually useless
+    #[serde(default)]
+    pub content: String,
+    /// event id
+    pub msg_id: String,
+    /// event time, unix timestamp in milliseconds
+    pub msg_timestamp: u64,
+    /// random string
+    #[serde(default)]
+    pub nonce: String,
+    /// detail event kind and body
+    pub extra: SystemEventKind,
+}
+
+/// Kind and body of a system event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "body", rename_all = "snake_case")]
+pub enum SystemEventKind {
+    // ===== channel =====
+    /// user added a reaction to a channel message
+    AddedReaction(Reaction),
+    /// user removed a reaction from a channel message
+    DeletedReaction(Reaction),
+    /// channel message updated
+    UpdatedMessage(UpdatedMessage),
+    /// channel message deleted
+    DeletedMessage(DeletedMessage),
+    /// channel message pinned
+    PinnedMessage(PinnedMessage),
+    /// channel message unpinned
+    UnpinnedMessage(PinnedMessage),
+    /// new channel added
+    AddedChannel(Channel),
+    /// channel info updated
+    UpdatedChannel(Channel),
+    /// channel deleted
+    DeletedChannel(DeletedChannel),
+
+    // ===== guild member =====
+    /// new member joined guild
+    JoinedGuild(JoinedGuild),
+    /// member exited guild
+    ExitedGuild(ExitedGuild),
+    /// member info updated
+    UpdatedGuildMember(UpdatedGuildMember),
+    /// member online
+    GuildMemberOnline(GuildMemberPresence),
+    /// member offline
+    GuildMemberOffline(GuildMemberPresence),
+
+    // ===== guild role =====
+    /// role added
+    AddedRole(Role),
+    /// role deleted
+    DeletedRole(Role),
+    /// role updated
+    UpdatedRole(Role),
+
+    // ===== guild =====
+    /// guild info updated
+    UpdatedGuild(Guild),
+    /// guild deleted
+    DeletedGuild(Guild),
+    /// user added to guild block list
+    AddedBlockList(AddedBlockList),
+    /// user removed from guild block list
+    DeletedBlockList(DeletedBlockList),
+
+    // ===== voice channel =====
+    /// user joined voice channel
+    JoinedChannel(JoinedChannel),
+    /// user exited voice channel
+    ExitedChannel(ExitedChannel),
+
+    // ===== private message =====
+    /// private message updated
+    UpdatedPrivateMessage(UpdatedPrivateMessage),
+    /// private message deleted
+    DeletedPrivateMessage(DeletedPrivateMessage),
+    /// user added a reaction to a private message
+    PrivateAddedReaction(PrivateReaction),
+    /// user removed a reaction from a private message
+    PrivateDeletedReaction(PrivateReaction),
+
+    // ===== user =====
+    /// user info updated
+    UserUpdated(UserUpdated),
+    /// bot joined a guild
+    SelfJoinedGuild(SelfGuild),
+    /// bot exited a guild
+    SelfExitedGuild(SelfGuild),
+    /// user clicked a button in card message
+    MessageBtnClick(ButtonClick),
+}
+
+/// Emoji used in reaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Emoji {
+    /// emoji id
+    pub id: String,
+    /// emoji name
+    pub name: String,
+}
+
+/// Body of channel message reaction events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reaction {
+    /// channel id
+    pub channel_id: String,
+    /// the emoji
+    pub emoji: Emoji,
+    /// user who reacted
+    pub user_id: String,
+    /// message id
+    pub msg_id: String,
+}
+
+/// Body of channel message update event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatedMessage {
+    /// channel id
+    pub channel_id: String,
+    /// new content
+    pub content: String,
+    /// mentioned user ids
+    #[serde(default)]
+    pub mention: Vec<String>,
+    /// if mentioned all users
+    #[serde(default)]
+    pub mention_all: bool,
+    /// if mentioned online users
+    #[serde(default)]
+    pub mention_here: bool,
+    /// mentioned role ids
+    #[serde(default)]
+    pub mention_roles: Vec<u64>,
+    /// update time, unix timestamp in milliseconds
+    pub updated_at: u64,
+    /// message id
+    pub msg_id: String,
+}
+
+/// Body of channel message delete event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedMessage {
+    /// channel id
+    pub channel_id: String,
+    /// message id
+    pub msg_id: String,
+}
+
+/// Body of channel message pin/unpin events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedMessage {
+    /// channel id
+    pub channel_id: String,
+    /// operator user id
+    pub operator_id: String,
+    /// message id
+    pub msg_id: String,
+}
+
+/// Body of channel delete event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedChannel {
+    /// channel id
+    pub id: String,
+    /// delete time, unix timestamp in milliseconds
+    pub deleted_at: u64,
+}
+
+/// Body of member join guild event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinedGuild {
+    /// user id
+    pub user_id: String,
+    /// join time, unix timestamp in milliseconds
+    pub joined_at: u64,
+}
+
+/// Body of member exit guild event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitedGuild {
+    /// user id
+    pub user_id: String,
+    /// exit time, unix timestamp in milliseconds
+    pub exited_at: u64,
+}
+
+/// Body of member info update event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatedGuildMember {
+    /// user id
+    pub user_id: String,
+    /// new nickname
+    pub nickname: String,
+}
+
+/// Body of member online/offline events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildMemberPresence {
+    /// user id
+    pub user_id: String,
+    /// event time, unix timestamp in milliseconds
+    pub event_time: u64,
+    /// guild ids shared with the bot
+    #[serde(default)]
+    pub guilds: Vec<String>,
+}
+
+/// Body of add block list event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddedBlockList {
+    /// operator user id
+    pub operator_id: String,
+    /// block reason
+    #[serde(default)]
+    pub remark: String,
+    /// blocked user ids
+    pub user_id: Vec<String>,
+}
+
+/// Body of remove block list event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedBlockList {
+    /// operator user id
+    pub operator_id: String,
+    /// unblocked user ids
+    pub user_id: Vec<String>,
+}
+
+/// Body of join voice channel event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinedChannel {
+    /// user id
+    pub user_id: String,
+    /// channel id
+    pub channel_id: String,
+    /// join time, unix timestamp in milliseconds
+    pub joined_at: u64,
+}
+
+/// Body of exit voice channel event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitedChannel {
+    /// user id
+    pub user_id: String,
+    /// channel id
+    pub channel_id: String,
+    /// exit time, unix timestamp in milliseconds
+    pub exited_at: u64,
+}
+
+/// Body of private message update event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatedPrivateMessage {
+    /// message id
+    pub msg_id: String,
+    /// sender user id
+    pub author_id: String,
+    /// receiver user id
+    pub target_id: String,
+    /// new content
+    pub content: String,
+    /// private chat code
+    pub chat_code: String,
+    /// update time, unix timestamp in milliseconds
+    pub updated_at: u64,
+}
+
+/// Body of private message delete event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedPrivateMessage {
+    /// message id
+    pub msg_id: String,
+    /// sender user id
+    pub author_id: String,
+    /// receiver user id
+    pub target_id: String,
+    /// private chat code
+    pub chat_code: String,
+    /// delete time, unix timestamp in milliseconds
+    pub deleted_at: u64,
+}
+
+/// Body of private message reaction events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivateReaction {
+    /// message id
+    pub msg_id: String,
+    /// user who reacted
+    pub user_id: String,
+    /// private chat code
+    pub chat_code: String,
+    /// the emoji
+    pub emoji: Emoji,
+}
+
+/// Body of user info update event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserUpdated {
+    /// user id
+    pub user_id: String,
+    /// new user name
+    pub username: String,
+    /// new avatar url
+    pub avatar: String,
+}
+
+/// Body of bot join/exit guild events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfGuild {
+    /// guild id
+    pub guild_id: String,
+}
+
+/// Body of card message button click event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonClick {
+    /// message id of the card
+    pub msg_id: String,
+    /// user who clicked
+    pub user_id: String,
+    /// value of the button
+    pub value: String,
+    /// channel id where the card is
+    pub target_id: String,
+    /// info of user who clicked
+    pub user_info: User,
+}
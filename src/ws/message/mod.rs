@@ -4,7 +4,7 @@ mod stream;
 mod types;
 
 pub use stream::{MessageStreamSink, MessageStreamSinkError};
-pub use types::{Hello, OnlyData, Reconnect, ResumeACK, SN};
+pub use types::{Hello, HelloCode, OnlyData, Reconnect, ReconnectCode, ResumeACK, SN};
 
 use bytes::Bytes;
 use enum_as_inner::EnumAsInner;
@@ -98,15 +98,20 @@ pub enum Message {
 }
 
 impl Message {
+    /// Decompress zlib compressed data
+    pub(crate) fn inflate(buff: Bytes) -> Result<Bytes, ParseMessageError> {
+        inflate::decompress_to_vec_zlib(&buff)
+            .map(Into::into)
+            .map_err(|e| ParseMessageError::DecompressFailed {
+                data: buff,
+                status: e,
+            })
+    }
+
     /// Decode data to a message
     pub fn decode(mut buff: Bytes, compressed: bool) -> Result<Self, ParseMessageError> {
         if compressed {
-            buff = inflate::decompress_to_vec_zlib(&buff)
-                .map_err(|e| ParseMessageError::DecompressFailed {
-                    data: buff.clone(),
-                    status: e,
-                })?
-                .into();
+            buff = Self::inflate(buff)?;
         }
 
         let mut value: serde_json::Value =
@@ -143,7 +148,7 @@ impl Message {
 
     /// encode data to binary message(without compress)
     pub fn encode(&self) -> Vec<u8> {
-        let mut value = serde_json::to_value(&self).unwrap();
+        let mut value = serde_json::to_value(self).unwrap();
         let obj = value.as_object_mut().unwrap();
         obj.remove(MESSAGE_INTERNAL_TYPE_TAG);
         obj.insert(
@@ -206,7 +211,7 @@ mod test {
             let msg = Message::decode(data, false).unwrap();
 
             if let Message::Hello(hello) = msg {
-                assert_eq!(hello.data.code, 0);
+                assert_eq!(hello.data.code, HelloCode::Success);
                 assert_eq!(hello.data.session_id.unwrap(), "some-session-id");
             } else {
                 panic!("decoded message is not hello")
@@ -277,7 +282,7 @@ mod test {
             let msg = Message::decode(data, false).unwrap();
 
             if let Message::Reconnect(reconnect) = msg {
-                assert_eq!(reconnect.data.code, 41008);
+                assert_eq!(reconnect.data.code, ReconnectCode::MissingParams);
                 assert_eq!(reconnect.data.err, "Missing params");
             } else {
                 panic!("decoded message is not reconnect")
@@ -312,12 +317,14 @@ mod test {
         fn test_message_encode_hello() {
             let msg = Message::Hello(OnlyData {
                 data: Hello {
-                    code: 0,
+                    code: HelloCode::Success,
                     session_id: Some("some-session-id".to_string()),
                 },
             });
 
-            println!("{:?}", msg.encode());
+            let value: serde_json::Value = serde_json::from_slice(&msg.encode()).unwrap();
+            assert_eq!(value["s"], 1);
+            assert_eq!(value["d"]["code"], 0);
         }
     }
 }
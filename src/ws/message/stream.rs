@@ -69,8 +69,9 @@ impl Stream for MessageStreamSink {
     ) -> Poll<Option<Self::Item>> {
         match self.ws.poll_next_unpin(cx) {
             Poll::Pending => Poll::Pending,
-            Poll::Ready(frame) => {
-                let frame = frame.unwrap().context(error::Websocket)?;
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Ready(Some(frame)) => {
+                let frame = frame.context(error::Websocket)?;
                 let result = match frame {
                     websocket::Message::Binary(data) => {
                         match Message::decode(data.into(), self.compress) {
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 /// A util structure to hold data filed
@@ -11,8 +13,8 @@ pub struct OnlyData<D> {
 /// Hello message data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hello {
-    /// status code, zero for success
-    pub code: i64,
+    /// status code
+    pub code: HelloCode,
     /// conversion session id, exist only when code is zero
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
@@ -29,7 +31,7 @@ pub struct SN {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reconnect {
     /// status code for why we need reconnect
-    pub code: i64,
+    pub code: ReconnectCode,
     /// reason for human read
     pub err: String,
 }
@@ -37,6 +39,152 @@ pub struct Reconnect {
 /// ReconnectACK message data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResumeACK {
-    /// conversion session id
+    /// conversion session id, empty if the server did not send one
+    #[serde(default)]
     pub session_id: String,
 }
+
+/// Status code of hello message
+///
+/// see <https://developer.kaiheila.cn/doc/websocket#%E4%BF%A1%E4%BB%A4[1]%20HELLO>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "i64", into = "i64")]
+pub enum HelloCode {
+    /// success, 0
+    Success,
+    /// missing params, 40100
+    MissingParams,
+    /// invalid token, 40101
+    InvalidToken,
+    /// token verify failed, 40102
+    TokenVerifyFailed,
+    /// token expired, 40103
+    TokenExpired,
+    /// other undocumented code
+    Other(i64),
+}
+
+impl HelloCode {
+    /// if the hello means connection succeed
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
+    }
+
+    /// if the connection is rejected because of token, a new gateway url is needed
+    pub fn is_token_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidToken | Self::TokenVerifyFailed | Self::TokenExpired
+        )
+    }
+
+    /// if last session can still be resumed after this hello.
+    ///
+    /// Only missing params means our resume arguments are broken.
+    pub fn can_resume(&self) -> bool {
+        *self != Self::MissingParams
+    }
+
+    fn meaning(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::MissingParams => "missing params",
+            Self::InvalidToken => "invalid token",
+            Self::TokenVerifyFailed => "token verify failed",
+            Self::TokenExpired => "token expired",
+            Self::Other(_) => "unknown",
+        }
+    }
+}
+
+impl From<i64> for HelloCode {
+    fn from(code: i64) -> Self {
+        match code {
+            0 => Self::Success,
+            40100 => Self::MissingParams,
+            40101 => Self::InvalidToken,
+            40102 => Self::TokenVerifyFailed,
+            40103 => Self::TokenExpired,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl From<HelloCode> for i64 {
+    fn from(code: HelloCode) -> Self {
+        match code {
+            HelloCode::Success => 0,
+            HelloCode::MissingParams => 40100,
+            HelloCode::InvalidToken => 40101,
+            HelloCode::TokenVerifyFailed => 40102,
+            HelloCode::TokenExpired => 40103,
+            HelloCode::Other(code) => code,
+        }
+    }
+}
+
+impl Display for HelloCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", i64::from(*self), self.meaning())
+    }
+}
+
+/// Status code of reconnect message, every code means last session can't be resumed
+///
+/// see <https://developer.kaiheila.cn/doc/websocket#%E4%BF%A1%E4%BB%A4[5]%20RECONNECT>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "i64", into = "i64")]
+pub enum ReconnectCode {
+    /// resume failed because of missing params, 40106
+    ResumeFailed,
+    /// session expired, 40107
+    SessionExpired,
+    /// invalid sn, or sn not exist anymore, 40108
+    InvalidSn,
+    /// missing params, 41008
+    MissingParams,
+    /// other undocumented code
+    Other(i64),
+}
+
+impl ReconnectCode {
+    fn meaning(&self) -> &'static str {
+        match self {
+            Self::ResumeFailed => "resume failed",
+            Self::SessionExpired => "session expired",
+            Self::InvalidSn => "invalid sn",
+            Self::MissingParams => "missing params",
+            Self::Other(_) => "unknown",
+        }
+    }
+}
+
+impl From<i64> for ReconnectCode {
+    fn from(code: i64) -> Self {
+        match code {
+            40106 => Self::ResumeFailed,
+            40107 => Self::SessionExpired,
+            40108 => Self::InvalidSn,
+            41008 => Self::MissingParams,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl From<ReconnectCode> for i64 {
+    fn from(code: ReconnectCode) -> Self {
+        match code {
+            ReconnectCode::ResumeFailed => 40106,
+            ReconnectCode::SessionExpired => 40107,
+            ReconnectCode::InvalidSn => 40108,
+            ReconnectCode::MissingParams => 41008,
+            ReconnectCode::Other(code) => code,
+        }
+    }
+}
+
+impl Display for ReconnectCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", i64::from(*self), self.meaning())
+    }
+}